@@ -0,0 +1,145 @@
+use crate::{
+    error::OrderError,
+    map::Map,
+    parse::{parse_province, parse_unit_type, tokenize},
+    province::ProvinceID,
+    unit::UnitType,
+};
+
+/// What happened to the adjustment orders of a winter phase (see `GameState::resolve_builds`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuildResult {
+    // The units that were built, by the province (or coast) they were built in.
+    pub built: Vec<(ProvinceID, UnitType)>,
+
+    // The provinces (or coasts) that units were disbanded from.
+    pub disbanded: Vec<ProvinceID>,
+
+    // How many builds were waived.
+    pub waived: usize,
+
+    // The orders that were rejected, and why.
+    pub rejected: Vec<(BuildOrder, OrderError)>,
+}
+
+/// An order given during a winter adjustment phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildOrder {
+    // Build a new unit in a home center. Fleets built in a center with split coasts (i.e. St. Petersburg) also say which
+    // coast they are built on.
+    Build {
+        province: ProvinceID,
+        unit_type: UnitType,
+        coast: Option<ProvinceID>,
+    },
+
+    // Remove the unit in `province`, which has to be of the type given.
+    Disband {
+        province: ProvinceID,
+        unit_type: UnitType,
+    },
+
+    // Choose not to use an available build.
+    Waive,
+}
+
+impl BuildOrder {
+    // Parses a build such as "Build A Par" or "Build F StP/nc", a disband such as "Disband A Mun", or "Waive".
+    pub fn parse(input: &str, map: &Map) -> Result<Self, OrderError> {
+        let tokens = tokenize(input);
+
+        match tokens.as_slice() {
+            [build, unit_type, province] if build.eq_ignore_ascii_case("build") => {
+                let unit_type = parse_unit_type(unit_type)?;
+                let province = parse_province(province, map)?;
+
+                // Naming a coast means building on that coast of its parent province.
+                Ok(match map.parent_of_coast(province) {
+                    Some(parent) => Self::Build {
+                        province: parent,
+                        unit_type,
+                        coast: Some(province),
+                    },
+                    None => Self::Build {
+                        province,
+                        unit_type,
+                        coast: None,
+                    },
+                })
+            }
+            [disband, unit_type, province] if disband.eq_ignore_ascii_case("disband") => {
+                Ok(Self::Disband {
+                    province: parse_province(province, map)?,
+                    unit_type: parse_unit_type(unit_type)?,
+                })
+            }
+            [waive] if waive.eq_ignore_ascii_case("waive") => Ok(Self::Waive),
+            _ => Err(OrderError::InvalidFormat(input.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(map: &Map, name: &str) -> ProvinceID {
+        map.province_by_name(name).unwrap()
+    }
+
+    #[test]
+    fn parses_an_army_build() {
+        let map = Map::standard();
+
+        assert_eq!(
+            BuildOrder::parse("Build A Par", &map),
+            Ok(BuildOrder::Build {
+                province: id(&map, "Par"),
+                unit_type: UnitType::Army,
+                coast: None
+            })
+        );
+    }
+
+    #[test]
+    fn parses_a_fleet_build_on_a_coast() {
+        let map = Map::standard();
+
+        assert_eq!(
+            BuildOrder::parse("Build F StP/nc", &map),
+            Ok(BuildOrder::Build {
+                province: id(&map, "StP"),
+                unit_type: UnitType::Fleet,
+                coast: Some(id(&map, "StP/nc"))
+            })
+        );
+    }
+
+    #[test]
+    fn parses_a_disband() {
+        let map = Map::standard();
+
+        assert_eq!(
+            BuildOrder::parse("Disband A Mun", &map),
+            Ok(BuildOrder::Disband {
+                province: id(&map, "Mun"),
+                unit_type: UnitType::Army
+            })
+        );
+    }
+
+    #[test]
+    fn parses_a_waive() {
+        let map = Map::standard();
+
+        assert_eq!(BuildOrder::parse("Waive", &map), Ok(BuildOrder::Waive));
+        assert_eq!(
+            BuildOrder::parse("Build Par", &map),
+            Err(OrderError::InvalidFormat("Build Par".to_string()))
+        );
+        assert_eq!(
+            BuildOrder::parse("Build F StP/ec", &map),
+            Err(OrderError::UnknownCoast("StP/ec".to_string()))
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{
+    error::OrderError,
+    game_state::{GameState, PhaseOrders, PhaseOutcome},
+    map::{Connection, Map},
+    order::{Order, order_for},
+    parse::{parse_province, parse_province_type, parse_unit_type, tokenize},
+    player::{Player, PlayerID},
+    province::{Province, ProvinceID, ProvinceType},
+    unit::{Unit, UnitType},
+    variant::VariantConfig,
+};
+
+/// A single movement phase test case in the style of the DATC (Diplomacy Adjudicator Test Cases), along with the small map
+/// it is played on.
+///
+/// Cases are written one line at a time, with anything after a `#` ignored:
+///
+/// ```text
+/// CASE 6.A.11 Simple bounce
+/// PROVINCE Vie Land
+/// PROVINCE Tyr Land
+/// PROVINCE Ven Land
+/// CONNECT Vie Tyr A
+/// CONNECT Tyr Ven AF
+/// UNIT Austria A Vie
+/// UNIT Italy A Ven
+/// ORDER A Vie - Tyr
+/// ORDER A Ven - Tyr
+/// EXPECT Vie bounced
+/// EXPECT Ven bounced
+/// ```
+///
+/// Instead of listing its provinces and connections, a case can be played on the standard map, with a `MAP standard` line
+/// before its units. A coast is written as `PROVINCE Spa/nc Coast Spa`, after its parent. Connections list the unit types that may use them
+/// (`A`, `F`, or `AF`). The outcome expected for each unit is one given by `Order::outcome`, and units without an `EXPECT`
+/// aren't checked.
+#[derive(Clone)]
+pub struct Case {
+    pub name: String,
+
+    // The map with every unit already placed.
+    pub map: Map,
+
+    // One player per power named on a `UNIT` line, numbered from 1 in the order they first appear.
+    pub players: Vec<Player>,
+
+    pub orders: Vec<Order>,
+
+    // The outcome expected for the unit in each province.
+    pub expected: Vec<(ProvinceID, String)>,
+}
+
+impl Case {
+    // Parses every case in `input`. Each case starts with a `CASE` line, and runs until the next one.
+    pub fn parse_all(input: &str) -> Result<Vec<Self>, OrderError> {
+        let mut cases: Vec<Self> = Vec::new();
+
+        for line in input.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            if keyword == "CASE" {
+                cases.push(Self {
+                    name: rest.to_string(),
+                    map: Map::new(),
+                    players: Vec::new(),
+                    orders: Vec::new(),
+                    expected: Vec::new(),
+                });
+                continue;
+            }
+
+            let case = cases
+                .last_mut()
+                .ok_or_else(|| OrderError::InvalidFormat(line.to_string()))?;
+            case.parse_line(keyword, rest)
+                .ok_or_else(|| OrderError::InvalidFormat(line.to_string()))??;
+        }
+
+        Ok(cases)
+    }
+
+    // Adds a single line to the case. None if the line isn't in any of the forms that a case line can take.
+    fn parse_line(&mut self, keyword: &str, rest: &str) -> Option<Result<(), OrderError>> {
+        let tokens = tokenize(rest);
+        let result = match (keyword, tokens.as_slice()) {
+            ("MAP", [name]) if name.eq_ignore_ascii_case("standard") => {
+                self.use_standard_map();
+                Ok(())
+            }
+            ("PROVINCE", [name, province_type]) => {
+                let province_type = parse_province_type(province_type)?;
+                self.add_province(name, province_type, None)
+            }
+            ("PROVINCE", [name, province_type, parent]) => {
+                let province_type = parse_province_type(province_type)?;
+                parse_province(parent, &self.map)
+                    .and_then(|parent| self.add_province(name, province_type, Some(parent)))
+            }
+            ("CONNECT", [province_1, province_2, unit_types]) => {
+                let unit_types = parse_connection_unit_types(unit_types)?;
+                parse_province(province_1, &self.map).and_then(|province_1| {
+                    let province_2 = parse_province(province_2, &self.map)?;
+                    let connection = Connection::new(province_1, province_2, unit_types)
+                        .map_err(|_| OrderError::InvalidFormat(rest.to_string()))?;
+                    self.map.add_connection(connection);
+                    Ok(())
+                })
+            }
+            ("UNIT", [power, unit_type, province]) => {
+                parse_unit_type(unit_type).and_then(|unit_type| {
+                    let province = parse_province(province, &self.map)?;
+                    let owner = self.player_id(power);
+                    if let Some(province) = self.map.province_mut(province) {
+                        province.set_occupant(Some(Unit::new(owner, unit_type)));
+                    }
+                    Ok(())
+                })
+            }
+            ("ORDER", _) => Order::parse(rest, &self.map).map(|order| self.orders.push(order)),
+            ("EXPECT", [province, outcome]) => parse_province(province, &self.map)
+                .map(|province| self.expected.push((province, outcome.to_lowercase()))),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    fn add_province(
+        &mut self,
+        name: &str,
+        province_type: ProvinceType,
+        parent: Option<ProvinceID>,
+    ) -> Result<(), OrderError> {
+        let mut province = Province::new(
+            self.map.province_count() as ProvinceID,
+            name.to_string(),
+            province_type,
+        );
+        if let Some(parent) = parent {
+            province.set_coast_of(parent);
+        }
+        self.map.add_province(province);
+        Ok(())
+    }
+
+    // Plays the case on the standard map, starting without any units, and with its seven powers as the players.
+    fn use_standard_map(&mut self) {
+        self.map = Map::standard();
+        for province_id in 0..self.map.province_count() {
+            if let Some(province) = self.map.province_mut(province_id as ProvinceID) {
+                province.set_occupant(None);
+            }
+        }
+        self.players = Player::standard_powers();
+    }
+
+    // The ID of the player called `name`, adding them if they haven't been seen yet.
+    fn player_id(&mut self, name: &str) -> PlayerID {
+        if let Some(player) = self
+            .players
+            .iter()
+            .find(|player| player.player_name() == name)
+        {
+            return player.player_id();
+        }
+
+        let player_id = self.players.len() as PlayerID + 1;
+        self.players.push(Player::new(player_id, name.to_string()));
+        player_id
+    }
+
+    // Adjudicates the case as a spring movement phase, and checks every expected outcome. Returns a description of each
+    // outcome that didn't match, i.e. "Ven: expected bounced, got success".
+    pub fn run(&self) -> Result<(), Vec<String>> {
+        let mut game = GameState::new(
+            self.map.clone(),
+            self.players.clone(),
+            VariantConfig::standard(),
+        )
+        .map_err(|error| vec![error.to_string()])?;
+        let outcomes = match game.adjudicate(PhaseOrders::Movement(self.orders.clone())) {
+            Ok(PhaseOutcome::Movement(adjudication)) => adjudication.outcomes,
+            Ok(_) => unreachable!("Movement orders always give a movement outcome"),
+            Err(error) => return Err(vec![error.to_string()]),
+        };
+
+        let name = |province: ProvinceID| {
+            self.map
+                .province(province)
+                .map_or_else(String::new, |province| province.province_name().to_string())
+        };
+        let mismatches: Vec<String> = self
+            .expected
+            .iter()
+            .filter_map(|(province, expected)| {
+                let actual =
+                    order_for(&outcomes, *province).map_or("no order", |order| order.outcome());
+                (actual != expected)
+                    .then(|| format!("{}: expected {expected}, got {actual}", name(*province)))
+            })
+            .collect();
+
+        if !mismatches.is_empty() {
+            return Err(mismatches);
+        }
+        Ok(())
+    }
+}
+
+fn parse_connection_unit_types(token: &str) -> Option<Vec<UnitType>> {
+    match token.to_ascii_lowercase().as_str() {
+        "a" => Some(vec![UnitType::Army]),
+        "f" => Some(vec![UnitType::Fleet]),
+        "af" => Some(vec![UnitType::Army, UnitType::Fleet]),
+        _ => None,
+    }
+}
+
+// Parses and runs every case in `input`, panicking with the name and mismatched outcomes of each case that fails.
+pub fn assert_cases(input: &str) {
+    let cases = Case::parse_all(input).expect("Every case should parse");
+    assert!(!cases.is_empty(), "No cases to run");
+
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|case| {
+            case.run()
+                .err()
+                .map(|mismatches| format!("{}: {}", case.name, mismatches.join("; ")))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases_file_passes() {
+        assert_cases(include_str!("../datc/cases.txt"));
+    }
+
+    #[test]
+    fn case_reports_mismatched_outcomes() {
+        let cases = Case::parse_all(
+            "CASE Bounce expected to succeed
+            PROVINCE Vie Land
+            PROVINCE Tyr Land
+            PROVINCE Ven Land
+            CONNECT Vie Tyr A
+            CONNECT Tyr Ven A
+            UNIT Austria A Vie
+            UNIT Italy A Ven
+            ORDER A Vie - Tyr
+            ORDER A Ven - Tyr
+            EXPECT Vie success",
+        )
+        .unwrap();
+
+        assert_eq!(
+            cases[0].run(),
+            Err(vec!["Vie: expected success, got bounced".to_string()])
+        );
+    }
+
+    #[test]
+    fn case_on_the_standard_map() {
+        assert_cases(
+            "CASE 6.A.11 Simple bounce
+            MAP standard
+            UNIT Austria A Vie
+            UNIT Italy A Ven
+            ORDER A Vie - Tyr
+            ORDER A Ven - Tyr
+            EXPECT Vie bounced
+            EXPECT Ven bounced
+
+            CASE 6.A.12 Bounce of three units
+            MAP standard
+            UNIT Austria A Vie
+            UNIT Germany A Mun
+            UNIT Italy A Ven
+            ORDER A Vie - Tyr
+            ORDER A Mun - Tyr
+            ORDER A Ven - Tyr
+            EXPECT Vie bounced
+            EXPECT Mun bounced
+            EXPECT Ven bounced",
+        );
+    }
+
+    #[test]
+    fn unknown_line_is_rejected() {
+        assert!(Case::parse_all("CASE Typo\nPROVINCE Vie").is_err());
+        assert!(Case::parse_all("UNIT Austria A Vie").is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use std::{error::Error, fmt};
+
+use crate::{game_state::Phase, player::PlayerID, province::ProvinceID};
+
+// Why a connection couldn't be made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionError {
+    // A connection from a province to itself.
+    SameProvince(ProvinceID),
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SameProvince(province) => {
+                write!(f, "province {province} can't be connected to itself")
+            }
+        }
+    }
+}
+
+impl Error for ConnectionError {}
+
+// Why a map isn't valid, or couldn't be built.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapError {
+    // A connection couldn't be made (see `ConnectionError`).
+    InvalidConnection(ConnectionError),
+
+    // A coast or connection refers to a province that isn't on the map.
+    UnknownProvince(ProvinceID),
+
+    // A coast whose parent doesn't list it as one of its coasts, or a province listed as a coast that isn't one.
+    InvalidCoast(ProvinceID),
+
+    // A coast that counts as a supply center itself, or that can be built on (an `sc_value` of 0) while its parent isn't a
+    // supply center. Only the parent counts.
+    CoastSupplyCenter(ProvinceID),
+
+    // A province given an ID other than the next one in order, i.e. in a province table.
+    ProvinceOutOfOrder(ProvinceID),
+
+    // A province or connection table without the column of this name.
+    MissingColumn(&'static str),
+
+    // The row on this line of a province or connection table couldn't be read, i.e. it names a player or province that
+    // doesn't exist.
+    InvalidRow(usize),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidConnection(error) => write!(f, "invalid connection: {error}"),
+            Self::UnknownProvince(province) => write!(f, "province {province} isn't on the map"),
+            Self::InvalidCoast(province) => {
+                write!(
+                    f,
+                    "province {province} doesn't match the coasts of its parent"
+                )
+            }
+            Self::CoastSupplyCenter(province) => {
+                write!(
+                    f,
+                    "coast {province} has a supply center value that only its parent should have"
+                )
+            }
+            Self::ProvinceOutOfOrder(province) => {
+                write!(f, "province {province} isn't the next province in order")
+            }
+            Self::MissingColumn(column) => write!(f, "the table has no \"{column}\" column"),
+            Self::InvalidRow(line) => write!(f, "the row on line {line} of the table isn't valid"),
+        }
+    }
+}
+
+impl Error for MapError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidConnection(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConnectionError> for MapError {
+    fn from(error: ConnectionError) -> Self {
+        Self::InvalidConnection(error)
+    }
+}
+
+// Why an order couldn't be parsed, or was rejected when it was submitted or built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrderError {
+    // The input didn't match any of the forms that order can take.
+    InvalidFormat(String),
+
+    // A unit type other than an army ("A") or fleet ("F").
+    UnknownUnitType(String),
+
+    // A province name that isn't on the map.
+    UnknownProvince(String),
+
+    // A coast that the province it is given with doesn't have (i.e. "Spa/ec").
+    UnknownCoast(String),
+
+    // There is no unit in the ordered province.
+    NoUnit(ProvinceID),
+
+    // The unit in the province can't do what it was ordered to (i.e. an army ordered to convoy, or a fleet to be convoyed).
+    UnitCannotPerform(ProvinceID),
+
+    // The unit in the ordered province belongs to a different player.
+    NotOwnUnit(ProvinceID),
+
+    // More than one order was given to the unit in the province.
+    DuplicateOrder(ProvinceID),
+
+    // The player has been eliminated, so can't give orders any more.
+    PlayerEliminated(PlayerID),
+
+    // Units can only be built in a home center that the player still owns.
+    NotOwnedHomeCenter(ProvinceID),
+
+    // There is already a unit in the province, or on one of its coasts.
+    ProvinceOccupied(ProvinceID),
+
+    // The unit can't be in the province at all (i.e. a fleet in an inland province).
+    CannotHoldUnit(ProvinceID),
+
+    // A fleet built in a province with split coasts must say which coast it is built on.
+    CoastRequired(ProvinceID),
+
+    // Only fleets are built on a coast, and only on a coast of the province being built in.
+    InvalidCoast(ProvinceID),
+
+    // There is no unit of the player's, of the type given, to disband in the province.
+    NoUnitToDisband(ProvinceID),
+
+    // The player has already used every build they were allowed this winter (see `GameState::build_allowance`).
+    NoBuildsLeft(ProvinceID),
+
+    // A unit was already built in the province (or on one of its coasts) this winter.
+    DuplicateBuild(ProvinceID),
+
+    // The player doesn't have more units than supply centers, or has already disbanded as many as they had to this winter.
+    NoDisbandsLeft(ProvinceID),
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat(input) => write!(f, "\"{input}\" isn't a valid order"),
+            Self::UnknownUnitType(token) => write!(f, "\"{token}\" isn't a unit type"),
+            Self::UnknownProvince(token) => write!(f, "\"{token}\" isn't a province on the map"),
+            Self::UnknownCoast(token) => write!(f, "\"{token}\" isn't a coast on the map"),
+            Self::NoUnit(province) => write!(f, "there is no unit in province {province}"),
+            Self::UnitCannotPerform(province) => {
+                write!(
+                    f,
+                    "the unit in province {province} can't carry out that order"
+                )
+            }
+            Self::NotOwnUnit(province) => {
+                write!(
+                    f,
+                    "the unit in province {province} belongs to another player"
+                )
+            }
+            Self::DuplicateOrder(province) => {
+                write!(
+                    f,
+                    "the unit in province {province} was given more than one order"
+                )
+            }
+            Self::PlayerEliminated(player) => write!(f, "player {player} has been eliminated"),
+            Self::NotOwnedHomeCenter(province) => {
+                write!(f, "province {province} isn't an owned home center")
+            }
+            Self::ProvinceOccupied(province) => write!(f, "province {province} is occupied"),
+            Self::CannotHoldUnit(province) => {
+                write!(f, "province {province} can't hold that type of unit")
+            }
+            Self::CoastRequired(province) => {
+                write!(f, "a fleet built in province {province} needs a coast")
+            }
+            Self::InvalidCoast(province) => {
+                write!(f, "province {province} isn't a valid coast to build on")
+            }
+            Self::NoUnitToDisband(province) => {
+                write!(f, "there is no unit to disband in province {province}")
+            }
+            Self::NoBuildsLeft(province) => {
+                write!(f, "there are no builds left for province {province}")
+            }
+            Self::DuplicateBuild(province) => {
+                write!(f, "a unit was already built in province {province}")
+            }
+            Self::NoDisbandsLeft(province) => {
+                write!(f, "there are no disbands left for province {province}")
+            }
+        }
+    }
+}
+
+impl Error for OrderError {}
+
+// Why a phase couldn't be adjudicated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdjudicationError {
+    // Resolution stopped making progress. Lists the node indices of the orders that were left unresolved.
+    Deadlock(Vec<usize>),
+
+    // The orders given can't be adjudicated in this phase (i.e. movement orders in a retreat phase).
+    WrongPhase(Phase),
+
+    // The map being adjudicated on isn't valid (see `Map::validate`).
+    InvalidMap(MapError),
+
+    // One of the orders given was rejected.
+    InvalidOrder(OrderError),
+}
+
+impl fmt::Display for AdjudicationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deadlock(nodes) => write!(f, "resolution deadlocked on orders {nodes:?}"),
+            Self::WrongPhase(phase) => write!(f, "those orders can't be adjudicated in {phase}"),
+            Self::InvalidMap(error) => write!(f, "invalid map: {error}"),
+            Self::InvalidOrder(error) => write!(f, "invalid order: {error}"),
+        }
+    }
+}
+
+impl Error for AdjudicationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidMap(error) => Some(error),
+            Self::InvalidOrder(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<MapError> for AdjudicationError {
+    fn from(error: MapError) -> Self {
+        Self::InvalidMap(error)
+    }
+}
+
+impl From<OrderError> for AdjudicationError {
+    fn from(error: OrderError) -> Self {
+        Self::InvalidOrder(error)
+    }
+}
+
+// Why a game can't be set up on the board it was given (see `GameState::new`), or why the units on the board aren't a valid
+// starting position (see `GameState::validate_setup`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetupError {
+    // A province's type isn't allowed by the variant (i.e. deep sea on a map for a variant without it).
+    DisabledProvinceType(ProvinceID),
+
+    // The unit can't be in the province at all (i.e. a fleet in an inland province).
+    CannotHoldUnit(ProvinceID),
+
+    // There is a unit in the province and another on one of its coasts, or on more than one of its coasts.
+    DoubleOccupancy(ProvinceID),
+
+    // The unit in the province belongs to a player that isn't part of the game.
+    UnknownOwner(ProvinceID),
+
+    // A unit was placed in a province that isn't on the map.
+    UnknownProvince(ProvinceID),
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DisabledProvinceType(province) => {
+                write!(
+                    f,
+                    "province {province} has a type that the variant doesn't allow"
+                )
+            }
+            Self::CannotHoldUnit(province) => {
+                write!(f, "the unit in province {province} can't be there")
+            }
+            Self::DoubleOccupancy(province) => {
+                write!(f, "province {province} has more than one unit in it")
+            }
+            Self::UnknownOwner(province) => {
+                write!(
+                    f,
+                    "the unit in province {province} belongs to an unknown player"
+                )
+            }
+            Self::UnknownProvince(province) => write!(f, "province {province} isn't on the map"),
+        }
+    }
+}
+
+impl Error for SetupError {}
+
+// Why a snapshot couldn't be loaded as a game (see `GameState::from_snapshot`).
+#[derive(Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    // Provinces must be listed in order of their IDs, starting at 0.
+    ProvinceOutOfOrder(ProvinceID),
+
+    // A coast or connection refers to a province that doesn't exist (or, for coasts, comes after the coast itself).
+    UnknownProvince(ProvinceID),
+
+    // A province's type isn't allowed by the variant.
+    DisabledProvinceType(ProvinceID),
+
+    // A connection from a province to itself.
+    InvalidConnection(ProvinceID, ProvinceID),
+
+    // An owner, core, or unit refers to a player that isn't part of the game.
+    UnknownPlayer(PlayerID),
+
+    // Bytes written with a format version this version of the crate can't read (see `GameSnapshot::to_bytes`).
+    #[cfg(feature = "bincode")]
+    UnsupportedVersion(u8),
+
+    // Bytes that aren't a snapshot at all, or that were cut short.
+    #[cfg(feature = "bincode")]
+    InvalidBytes,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProvinceOutOfOrder(province) => {
+                write!(f, "province {province} isn't the next province in order")
+            }
+            Self::UnknownProvince(province) => {
+                write!(f, "province {province} isn't in the snapshot")
+            }
+            Self::DisabledProvinceType(province) => {
+                write!(
+                    f,
+                    "province {province} has a type that the variant doesn't allow"
+                )
+            }
+            Self::InvalidConnection(province_1, province_2) => {
+                write!(
+                    f,
+                    "provinces {province_1} and {province_2} can't be connected"
+                )
+            }
+            Self::UnknownPlayer(player) => write!(f, "player {player} isn't part of the game"),
+            #[cfg(feature = "bincode")]
+            Self::UnsupportedVersion(version) => {
+                write!(f, "snapshot format version {version} isn't supported")
+            }
+            #[cfg(feature = "bincode")]
+            Self::InvalidBytes => write!(f, "the bytes aren't a valid snapshot"),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+// Why `GameState::rewind` couldn't go back a phase.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RewindError {
+    // No phase has been adjudicated yet, so there is nothing to go back to.
+    NoHistory,
+
+    // The state saved before the phase couldn't be loaded again.
+    InvalidSnapshot(SnapshotError),
+}
+
+impl fmt::Display for RewindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoHistory => write!(f, "there is no adjudicated phase to go back to"),
+            Self::InvalidSnapshot(error) => write!(f, "invalid snapshot: {error}"),
+        }
+    }
+}
+
+impl Error for RewindError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidSnapshot(error) => Some(error),
+            Self::NoHistory => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_describe_what_went_wrong() {
+        assert_eq!(
+            OrderError::InvalidFormat("A Par Bur".to_string()).to_string(),
+            "\"A Par Bur\" isn't a valid order"
+        );
+        assert_eq!(
+            MapError::MissingColumn("province_id").to_string(),
+            "the table has no \"province_id\" column"
+        );
+        assert_eq!(
+            RewindError::NoHistory.to_string(),
+            "there is no adjudicated phase to go back to"
+        );
+    }
+
+    #[test]
+    fn wrapped_errors_are_described_and_given_as_the_source() {
+        let error = AdjudicationError::from(MapError::InvalidCoast(4));
+        assert_eq!(
+            error.to_string(),
+            "invalid map: province 4 doesn't match the coasts of its parent"
+        );
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            MapError::InvalidCoast(4).to_string()
+        );
+
+        let error = RewindError::InvalidSnapshot(SnapshotError::UnknownPlayer(9));
+        assert_eq!(
+            error.to_string(),
+            "invalid snapshot: player 9 isn't part of the game"
+        );
+        assert!(error.source().is_some());
+    }
+}
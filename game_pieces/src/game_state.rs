@@ -0,0 +1,2710 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    build::{BuildOrder, BuildResult},
+    error::{AdjudicationError, OrderError, RewindError, SetupError, SnapshotError},
+    map::{Connection, Map},
+    order::{self, Order, OrderSet, OrderType, ResolutionStats, ResolutionStep},
+    player::{NEUTRAL, Player, PlayerID},
+    province::{Province, ProvinceID, ProvinceType},
+    retreat::RetreatOrder,
+    unit::{Unit, UnitType},
+    variant::VariantConfig,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Phase {
+    SpringMovement,
+    SpringRetreat,
+    FallMovement,
+    FallRetreat,
+    WinterBuild,
+}
+
+impl Phase {
+    // The phase along with the year it is in, i.e. "Spring 1901 Movement".
+    pub fn describe(&self, year: u16) -> String {
+        let (season, kind) = self.parts();
+        format!("{season} {year} {kind}")
+    }
+
+    // The season and the kind of orders the phase takes, i.e. ("Spring", "Movement").
+    fn parts(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::SpringMovement => ("Spring", "Movement"),
+            Self::SpringRetreat => ("Spring", "Retreat"),
+            Self::FallMovement => ("Fall", "Movement"),
+            Self::FallRetreat => ("Fall", "Retreat"),
+            Self::WinterBuild => ("Winter", "Build"),
+        }
+    }
+}
+
+// Without the year, i.e. "Spring Movement". See `Phase::describe` for a name with the year in it.
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (season, kind) = self.parts();
+        write!(f, "{season} {kind}")
+    }
+}
+
+/// The orders for a single phase, of the kind that phase takes: movement orders in spring and fall, retreat orders in the
+/// retreat phases, and build orders in winter.
+#[derive(Clone, Debug)]
+pub enum PhaseOrders {
+    Movement(Vec<Order>),
+    Retreat(Vec<RetreatOrder>),
+    Build(Vec<BuildOrder>),
+}
+
+/// The result of adjudicating a phase with `GameState::adjudicate`, matching the kind of orders given.
+#[derive(Clone, Debug)]
+pub enum PhaseOutcome {
+    Movement(Adjudication),
+
+    Retreat(RetreatOutcome),
+
+    // The builds and disbands that were carried out, along with the ones that were rejected (see
+    // `GameState::resolve_builds`).
+    Build(BuildResult),
+}
+
+/// The full state of a game in progress: the board, the players, and where in the turn order the game is.
+pub struct GameState {
+    map: Map,
+
+    players: Vec<Player>,
+
+    config: VariantConfig,
+
+    phase: Phase,
+
+    year: u16,
+
+    // Every phase adjudicated so far, oldest first. This is only trimmed by `rewind`, so it grows by one record per
+    // adjudicated phase for the whole game (phases that were skipped have no record).
+    history: Vec<TurnRecord>,
+
+    // The orders submitted for the current phase, at most one set per player.
+    submissions: Vec<OrderSet>,
+}
+
+impl GameState {
+    // Starts a game at the beginning of the variant's first year. Every province on the map must be of a type the variant
+    // allows, the same as for a game loaded with `from_snapshot`.
+    pub fn new(map: Map, players: Vec<Player>, config: VariantConfig) -> Result<Self, SetupError> {
+        if let Some(province) = map
+            .provinces()
+            .find(|province| !config.is_enabled(&province.province_type()))
+        {
+            return Err(SetupError::DisabledProvinceType(province.province_id()));
+        }
+
+        Ok(Self {
+            map,
+            players,
+            phase: Phase::SpringMovement,
+            year: config.starting_year(),
+            config,
+            history: Vec::new(),
+            submissions: Vec::new(),
+        })
+    }
+
+    pub fn map(&self) -> &Map {
+        &self.map
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn config(&self) -> &VariantConfig {
+        &self.config
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn history(&self) -> &[TurnRecord] {
+        &self.history
+    }
+
+    // Undoes the last adjudicated phase, putting the game back exactly as it was before that phase was adjudicated. The
+    // phase's record is removed from the history, and any orders submitted since are dropped.
+    pub fn rewind(&mut self) -> Result<(), RewindError> {
+        let record = self.history.last().ok_or(RewindError::NoHistory)?;
+        let restored =
+            Self::from_snapshot(record.before.clone()).map_err(RewindError::InvalidSnapshot)?;
+
+        self.history.pop();
+        self.map = restored.map;
+        self.players = restored.players;
+        self.config = restored.config;
+        self.phase = restored.phase;
+        self.year = restored.year;
+        self.submissions.clear();
+        Ok(())
+    }
+
+    // Checks that the units on the board make a valid starting position: every unit must be able to be where it is (i.e. no
+    // fleets in inland provinces, or on a province with split coasts rather than one of its coasts), no province may have a
+    // unit both in it and on one of its coasts, and every unit must belong to one of the players.
+    pub fn validate_setup(&self) -> Result<(), Vec<SetupError>> {
+        let mut errors = Vec::new();
+
+        for (province_id, unit) in self.map.occupied() {
+            let Some(province) = self.map.province(province_id) else {
+                continue;
+            };
+            if !self.can_start_in(province, unit.get_type()) {
+                errors.push(SetupError::CannotHoldUnit(province_id));
+            }
+
+            if !self.is_player(unit.owner()) {
+                errors.push(SetupError::UnknownOwner(province_id));
+            }
+        }
+
+        for province in self.map.provinces() {
+            let units = std::iter::once(province.province_id())
+                .chain(province.has_coasts().iter().copied())
+                .filter(|id| {
+                    self.map
+                        .province(*id)
+                        .is_some_and(|province| province.occupied_by().is_some())
+                })
+                .count();
+            if units > 1 {
+                errors.push(SetupError::DoubleOccupancy(province.province_id()));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(())
+    }
+
+    // Places the starting units for a game, i.e. the 22 units of the standard opening. Each placement is checked the same
+    // way as `validate_setup`, and must not share a province (counting coasts) with a unit already on the board or with
+    // another placement. Nothing is placed unless every placement is valid.
+    pub fn place_units(
+        &mut self,
+        placements: Vec<(ProvinceID, PlayerID, UnitType)>,
+    ) -> Result<(), Vec<SetupError>> {
+        let mut errors = Vec::new();
+        let mut placed: Vec<ProvinceID> = Vec::new();
+
+        for (province_id, owner, unit_type) in &placements {
+            let Some(province) = self.map.province(*province_id) else {
+                errors.push(SetupError::UnknownProvince(*province_id));
+                continue;
+            };
+
+            if !self.can_start_in(province, *unit_type) {
+                errors.push(SetupError::CannotHoldUnit(*province_id));
+            }
+
+            if !self.is_player(*owner) {
+                errors.push(SetupError::UnknownOwner(*province_id));
+            }
+
+            if self.map.is_province_occupied_including_coasts(*province_id)
+                || placed
+                    .iter()
+                    .any(|other| self.map.is_same_province(*other, *province_id))
+            {
+                errors.push(SetupError::DoubleOccupancy(*province_id));
+            }
+            placed.push(*province_id);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        for (province_id, owner, unit_type) in placements {
+            if let Some(province) = self.map.province_mut(province_id) {
+                province.set_occupant(Some(Unit::new(owner, unit_type)));
+            }
+        }
+        Ok(())
+    }
+
+    // Whether a unit can start the game in `province`. Fleets can't be inland, or in a province with split coasts rather
+    // than on one of its coasts.
+    fn can_start_in(&self, province: &Province, unit_type: UnitType) -> bool {
+        let inland = unit_type == UnitType::Fleet
+            && (self
+                .map
+                .neighbors(province.province_id(), &unit_type)
+                .is_empty()
+                || !province.has_coasts().is_empty());
+        province.province_type().can_hold(&unit_type) && !inland
+    }
+
+    fn is_player(&self, player_id: PlayerID) -> bool {
+        self.players
+            .iter()
+            .any(|player| player.player_id() == player_id)
+    }
+
+    // Moves on to the next phase, starting a new year after the winter builds. Anything left over from the last movement
+    // phase (i.e. standoffs) is cleared once its retreats are over.
+    // Phases where nobody has anything to do are skipped: retreat phases when no unit was dislodged, and winter builds when
+    // every player has as many units as supply centers.
+    // Supply centers change hands once the fall is over, after any fall retreats, so that a unit retreating into a center
+    // can take it. Returns the centers that changed hands, if the fall ended.
+    pub fn advance_phase(&mut self) -> Vec<(ProvinceID, PlayerID, PlayerID)> {
+        let mut sc_transfers = Vec::new();
+        loop {
+            self.phase = match self.phase {
+                Phase::SpringMovement => Phase::SpringRetreat,
+                Phase::SpringRetreat => Phase::FallMovement,
+                Phase::FallMovement => Phase::FallRetreat,
+                Phase::FallRetreat => Phase::WinterBuild,
+                Phase::WinterBuild => {
+                    self.year += 1;
+                    Phase::SpringMovement
+                }
+            };
+
+            if matches!(self.phase, Phase::FallMovement | Phase::SpringMovement) {
+                for id in 0..self.map.province_count() as ProvinceID {
+                    if let Some(province) = self.map.province_mut(id) {
+                        province.reset_turn_state();
+                    }
+                }
+            }
+
+            if self.phase == Phase::WinterBuild {
+                sc_transfers = self.transfer_supply_centers();
+            }
+
+            let skip = match self.phase {
+                Phase::SpringRetreat | Phase::FallRetreat => self.map.dislodged_units().is_empty(),
+                Phase::WinterBuild => self.players.iter().all(|player| {
+                    self.supply_center_count(player.player_id()) as usize
+                        == self.unit_count(player.player_id())
+                }),
+                Phase::SpringMovement | Phase::FallMovement => false,
+            };
+            if !skip {
+                return sc_transfers;
+            }
+        }
+    }
+
+    // Every unit on the board, in order of province, not counting any waiting to retreat. A fleet on a coast is listed on
+    // that coast rather than its parent province.
+    pub fn units(&self) -> Vec<UnitView> {
+        self.map
+            .occupied()
+            .map(|(province, unit)| UnitView {
+                province,
+                owner: unit.owner(),
+                unit_type: unit.get_type(),
+            })
+            .collect()
+    }
+
+    // The number of units `player` has on the board, not counting any waiting to retreat.
+    pub fn unit_count(&self, player: PlayerID) -> usize {
+        self.map
+            .occupied()
+            .filter(|(_, unit)| unit.owner() == player)
+            .count()
+    }
+
+    // The players who have been knocked out of the game: they own no supply centers, and have no units left, including any
+    // waiting to retreat.
+    pub fn eliminated_players(&self) -> Vec<PlayerID> {
+        let dislodged = self.map.dislodged_units();
+        self.players
+            .iter()
+            .map(|player| player.player_id())
+            .filter(|player| {
+                self.supply_center_count(*player) == 0
+                    && self.unit_count(*player) == 0
+                    && !dislodged.iter().any(|(_, unit)| unit.owner() == *player)
+            })
+            .collect()
+    }
+
+    // Submits a player's orders for the current phase, replacing any they submitted before. The whole set is rejected if any
+    // of the orders are for a unit that isn't theirs, if a unit is given more than one order, or if the player has been
+    // eliminated.
+    pub fn submit(&mut self, set: OrderSet) -> Result<(), Vec<OrderError>> {
+        if self.eliminated_players().contains(&set.player) {
+            return Err(vec![OrderError::PlayerEliminated(set.player)]);
+        }
+
+        let errors: Vec<OrderError> = set
+            .orders
+            .iter()
+            .filter_map(
+                |order| match self.map.occupant_including_coasts(order.order_of()) {
+                    None => Some(OrderError::NoUnit(order.order_of())),
+                    Some((_, unit)) if unit.owner() != set.player => {
+                        Some(OrderError::NotOwnUnit(order.order_of()))
+                    }
+                    Some(_) => None,
+                },
+            )
+            .chain(self.duplicate_orders(&set.orders))
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        self.submissions
+            .retain(|submission| submission.player != set.player);
+        self.submissions.push(set);
+        Ok(())
+    }
+
+    // An error for every order given to a unit that was already given an order earlier in `orders`. Neither order is picked
+    // over the other, so the orders have to be rejected.
+    fn duplicate_orders(&self, orders: &[Order]) -> Vec<OrderError> {
+        orders
+            .iter()
+            .enumerate()
+            .filter(|(index, order)| {
+                orders[..*index].iter().any(|earlier| {
+                    self.map
+                        .is_same_province(earlier.order_of(), order.order_of())
+                })
+            })
+            .map(|(_, order)| OrderError::DuplicateOrder(order.order_of()))
+            .collect()
+    }
+
+    // Every order submitted so far for the current phase, merged into one list to be adjudicated.
+    pub fn submitted_orders(&self) -> Vec<Order> {
+        self.submissions
+            .iter()
+            .flat_map(|submission| submission.orders.iter().copied())
+            .collect()
+    }
+
+    // Adjudicates the current phase with the given orders, and moves on to the next phase (see `advance_phase`). The orders
+    // must be the kind the phase takes; otherwise nothing happens and the phase is returned as the error.
+    pub fn adjudicate(&mut self, orders: PhaseOrders) -> Result<PhaseOutcome, AdjudicationError> {
+        self.adjudicate_with(orders, false)
+            .map(|(outcome, _)| outcome)
+    }
+
+    // The same as `adjudicate`, but also returns every decision made while resolving a movement phase, in the order they
+    // were made (see `order::resolve_orders_traced`). Retreat and build phases have nothing to trace, so give an empty
+    // trace.
+    pub fn adjudicate_traced(
+        &mut self,
+        orders: PhaseOrders,
+    ) -> Result<(PhaseOutcome, Vec<ResolutionStep>), AdjudicationError> {
+        self.adjudicate_with(orders, true)
+    }
+
+    fn adjudicate_with(
+        &mut self,
+        orders: PhaseOrders,
+        tracing: bool,
+    ) -> Result<(PhaseOutcome, Vec<ResolutionStep>), AdjudicationError> {
+        self.map.validate()?;
+        let (phase, year, before) = (self.phase, self.year, self.to_snapshot());
+        let given = orders.clone();
+
+        let (outcome, trace) = match (self.phase, orders) {
+            (Phase::SpringMovement | Phase::FallMovement, PhaseOrders::Movement(orders)) => self
+                .adjudicate_movement(&orders, tracing)
+                .map(|(adjudication, trace)| (PhaseOutcome::Movement(adjudication), trace)),
+            (Phase::SpringRetreat | Phase::FallRetreat, PhaseOrders::Retreat(orders)) => {
+                let disbanded = self.resolve_retreats(&orders);
+                let sc_transfers = self.advance_phase();
+                Ok((
+                    PhaseOutcome::Retreat(RetreatOutcome {
+                        disbanded,
+                        sc_transfers,
+                    }),
+                    Vec::new(),
+                ))
+            }
+            (Phase::WinterBuild, PhaseOrders::Build(orders)) => {
+                // Each build is for whoever owns the center, and each disband for whoever owns the unit. Every player's
+                // allowance is taken before any of the orders are resolved, as in `resolve_builds`.
+                let players: Vec<PlayerID> = orders
+                    .iter()
+                    .map(|order| match *order {
+                        BuildOrder::Build { province, .. } => self
+                            .map
+                            .province(province)
+                            .map_or(NEUTRAL, |province| province.owned_by()),
+                        BuildOrder::Disband { province, .. } => {
+                            self.map.occupant_owner(province).unwrap_or(NEUTRAL)
+                        }
+                        BuildOrder::Waive => NEUTRAL,
+                    })
+                    .collect();
+                let mut allowances: HashMap<PlayerID, i32> = players
+                    .iter()
+                    .map(|player| (*player, self.build_allowance(*player)))
+                    .collect();
+
+                let mut result = BuildResult::default();
+                for (order, player) in orders.iter().zip(players) {
+                    if let Some(allowance) = allowances.get_mut(&player) {
+                        self.resolve_build(player, order, allowance, &mut result);
+                    }
+                }
+                self.advance_phase();
+                Ok((PhaseOutcome::Build(result), Vec::new()))
+            }
+            (phase, _) => Err(AdjudicationError::WrongPhase(phase)),
+        }?;
+
+        self.history.push(TurnRecord {
+            phase,
+            year,
+            before,
+            orders: given,
+            outcome: outcome.clone(),
+        });
+        Ok((outcome, trace))
+    }
+
+    // Adjudicates a movement phase: units without orders hold, and the orders are resolved and applied to the board. A
+    // fall phase with nothing to retreat ends the fall, so supply centers are then taken by whoever occupies them (see
+    // `advance_phase`). Returns the resolved orders along with any supply centers that changed hands, and any orders
+    // left out because there was no unit to give them to. Any submitted orders (see `submit`) are cleared, so pass in
+    // `submitted_orders` to adjudicate those. If the orders give a unit more than one order, or can't be resolved, the
+    // board is left as it was.
+    fn adjudicate_movement(
+        &mut self,
+        orders: &[Order],
+        tracing: bool,
+    ) -> Result<(Adjudication, Vec<ResolutionStep>), AdjudicationError> {
+        if let Some(error) = self.duplicate_orders(orders).into_iter().next() {
+            return Err(error.into());
+        }
+
+        // An order for a province with no unit in it can't be carried out, and would otherwise act like a unit that isn't
+        // there (i.e. a move that could bounce a real one), so it is left out and reported instead.
+        let (given, ignored): (Vec<Order>, Vec<Order>) = orders.iter().partition(|order| {
+            self.map
+                .is_province_occupied_including_coasts(order.order_of())
+        });
+
+        let all_orders = self.fill_missing_with_holds(given);
+        let (outcomes, stats, trace) =
+            order::resolve_orders_for_variant(&all_orders, &self.map, &self.config, tracing)?;
+        let occupied_before = self.occupancy();
+        self.apply_movements(&outcomes);
+        let occupied_after = self.occupancy();
+
+        self.submissions.clear();
+        let sc_transfers = self.advance_phase();
+
+        Ok((
+            Adjudication {
+                outcomes,
+                sc_transfers,
+                vacated: self
+                    .newly_occupied(&occupied_after, &occupied_before)
+                    .into_iter()
+                    .map(|(province, _)| province)
+                    .collect(),
+                newly_occupied: self.newly_occupied(&occupied_before, &occupied_after),
+                ignored,
+                stats,
+            },
+            trace,
+        ))
+    }
+
+    // Where every unit on the board is, along with who owns it.
+    fn occupancy(&self) -> Vec<(ProvinceID, PlayerID)> {
+        self.map
+            .occupied()
+            .map(|(province, unit)| (province, unit.owner()))
+            .collect()
+    }
+
+    // The units in `after` that are in a province (counting its coasts) that no unit in `before` was in.
+    fn newly_occupied(
+        &self,
+        before: &[(ProvinceID, PlayerID)],
+        after: &[(ProvinceID, PlayerID)],
+    ) -> Vec<(ProvinceID, PlayerID)> {
+        after
+            .iter()
+            .filter(|(province, _)| {
+                !before
+                    .iter()
+                    .any(|(other, _)| self.map.is_same_province(*other, *province))
+            })
+            .copied()
+            .collect()
+    }
+
+    // Gives every supply center to the player whose unit is in it (or on one of its coasts), returning each one that changed
+    // hands as `(province, old owner, new owner)`. Empty supply centers keep their owner.
+    fn transfer_supply_centers(&mut self) -> Vec<(ProvinceID, PlayerID, PlayerID)> {
+        let transfers: Vec<(ProvinceID, PlayerID, PlayerID)> = self
+            .map
+            .provinces()
+            .filter(|province| province.is_supply_center())
+            .filter_map(|province| {
+                let owner = self.map.occupant_owner(province.province_id())?;
+                (owner != province.owned_by()).then_some((
+                    province.province_id(),
+                    province.owned_by(),
+                    owner,
+                ))
+            })
+            .collect();
+
+        for (province_id, _, new_owner) in &transfers {
+            if let Some(province) = self.map.province_mut(*province_id) {
+                province.set_owner(*new_owner);
+            }
+        }
+
+        transfers
+    }
+
+    // The number of supply centers owned by `player`. Coasts of supply centers are never counted (their `sc_value` is 0),
+    // and neither are neutral supply centers, as `NEUTRAL` isn't a player.
+    pub fn supply_center_count(&self, player: PlayerID) -> u8 {
+        self.map
+            .provinces()
+            .filter(|province| !province.is_neutral() && province.owned_by() == player)
+            .map(|province| province.sc_value().unwrap_or(0))
+            .sum()
+    }
+
+    // How many units the player may build this winter, or, if negative, how many they must disband: the difference between
+    // their supply centers and their units.
+    pub fn build_allowance(&self, player: PlayerID) -> i32 {
+        self.supply_center_count(player) as i32 - self.unit_count(player) as i32
+    }
+
+    // The home centers the player could build a unit in right now: ones they still own, that are empty, and that can take
+    // an army or a fleet (on any of their coasts).
+    pub fn open_build_centers(&self, player: PlayerID) -> Vec<ProvinceID> {
+        self.map
+            .home_centers(player)
+            .into_iter()
+            .filter(|province_id| {
+                let coasts = self
+                    .map
+                    .province(*province_id)
+                    .map_or(&[][..], |province| province.has_coasts());
+                let mut builds = vec![(UnitType::Army, None), (UnitType::Fleet, None)];
+                builds.extend(coasts.iter().map(|coast| (UnitType::Fleet, Some(*coast))));
+
+                builds.into_iter().any(|(unit_type, coast)| {
+                    let order = BuildOrder::Build {
+                        province: *province_id,
+                        unit_type,
+                        coast,
+                    };
+                    self.check_build(player, &order).is_ok()
+                })
+            })
+            .collect()
+    }
+
+    // Every supply center the player doesn't own yet that one of their units could move into next turn, in order of their
+    // IDs. A unit can reach the supply centers next to it, and an army can also reach any supply center it could be convoyed
+    // to by the player's own fleets. Other players' units are ignored, so a target may not actually be taken.
+    pub fn reachable_targets(&self, player: PlayerID) -> Vec<ProvinceID> {
+        let is_target = |province_id: ProvinceID| {
+            self.map.province(province_id).is_some_and(|province| {
+                province.is_supply_center() && province.owned_by() != player
+            })
+        };
+        let own_units: Vec<(ProvinceID, &Unit)> = self
+            .map
+            .occupied()
+            .filter(|(_, unit)| unit.owner() == player)
+            .collect();
+        let own_fleets: Vec<ProvinceID> = own_units
+            .iter()
+            .filter(|(_, unit)| unit.get_type() == UnitType::Fleet)
+            .map(|(province_id, _)| *province_id)
+            .collect();
+
+        let mut targets = Vec::new();
+        for (province_id, unit) in &own_units {
+            for next in self.map.neighbors(*province_id, &unit.get_type()) {
+                // A fleet moving onto a coast takes the province the coast is part of.
+                let next = self.map.parent_of_coast(next).unwrap_or(next);
+                if is_target(next) {
+                    targets.push(next);
+                }
+            }
+
+            if unit.get_type() == UnitType::Army {
+                targets.extend(
+                    self.map
+                        .supply_centers()
+                        .into_iter()
+                        .map(|(target, _)| target)
+                        .filter(|target| {
+                            is_target(*target) && !self.map.is_same_province(*target, *province_id)
+                        })
+                        .filter(|target| {
+                            !self
+                                .map
+                                .convoy_routes(*province_id, *target, &own_fleets)
+                                .is_empty()
+                        }),
+                );
+            }
+        }
+
+        targets.sort();
+        targets.dedup();
+        targets
+    }
+
+    // The player who owns enough supply centers to win the game, if any.
+    pub fn winner(&self) -> Option<PlayerID> {
+        self.players
+            .iter()
+            .map(|player| player.player_id())
+            .find(|player| self.supply_center_count(*player) >= self.config.solo_win_threshold())
+    }
+
+    // Adds a `Hold` order for every unit on the board that wasn't given an order, as unordered units hold by default. An
+    // order for a province counts as an order for a unit on any of its coasts, i.e. "F Spa - MAO" for a fleet on Spa/nc.
+    pub fn fill_missing_with_holds(&self, mut orders: Vec<Order>) -> Vec<Order> {
+        let unordered: Vec<ProvinceID> = self
+            .map
+            .occupied()
+            .map(|(id, _)| id)
+            .filter(|id| {
+                !orders
+                    .iter()
+                    .any(|order| self.map.is_same_province(order.order_of(), *id))
+            })
+            .collect();
+
+        for id in unordered {
+            orders.push(Order::hold(id));
+        }
+
+        orders
+    }
+
+    // Updates the board with the results of a resolved movement phase (see `order::resolve_orders`).
+    // Units that moved successfully are placed in their destinations, dislodged units are moved to `disloged_unit` to await
+    // the retreat phase, and every other unit stays where it is. A unit is found on whichever coast of the ordered province
+    // it is on.
+    pub fn apply_movements(&mut self, resolved_orders: &[Order]) {
+        // Pick up every unit that is moving first, so that units moving in a chain or circle don't overwrite each other.
+        let mut moving_units = Vec::new();
+        for order in resolved_orders {
+            if order.order_type() == OrderType::Move
+                && let Some((origin, _)) = self.map.occupant_including_coasts(order.order_of())
+                && let Some(unit) = self
+                    .map
+                    .province_mut(origin)
+                    .and_then(|province| province.take_occupant())
+            {
+                moving_units.push((origin, order.order_to(), unit));
+            }
+        }
+
+        for order in resolved_orders {
+            if !order.is_dislodged() {
+                continue;
+            }
+
+            let attacker_origin = moving_units
+                .iter()
+                .find(|(_, destination, _)| {
+                    self.map.is_same_province(*destination, order.order_of())
+                })
+                .map(|(origin, _, _)| *origin);
+
+            if let Some((location, _)) = self.map.occupant_including_coasts(order.order_of())
+                && let Some(province) = self.map.province_mut(location)
+            {
+                let unit = province.take_occupant();
+                province.set_disloged_unit(unit);
+                province.set_dislodged_by(attacker_origin);
+            }
+        }
+
+        for (origin, destination, unit) in moving_units {
+            // A unit that wasn't given an order has no order to be marked dislodged, but if a unit moved into its province
+            // (or one of its coasts) it was still dislodged.
+            if let Some((occupied, _)) = self.map.occupant_including_coasts(destination)
+                && let Some(province) = self.map.province_mut(occupied)
+            {
+                let dislodged_unit = province.take_occupant();
+                province.set_disloged_unit(dislodged_unit);
+                province.set_dislodged_by(Some(origin));
+            }
+
+            if let Some(province) = self.map.province_mut(destination) {
+                province.set_occupant(Some(unit));
+            }
+        }
+
+        self.mark_standoffs(resolved_orders);
+    }
+
+    // A province that several units bounced out of and that was left empty is a standoff, and can't be retreated to this
+    // turn. Every other province is available again, as standoffs only last until the retreats that follow them.
+    fn mark_standoffs(&mut self, resolved_orders: &[Order]) {
+        let standoffs: Vec<ProvinceID> = self
+            .map
+            .provinces()
+            .filter(|province| province.is_coast_of().is_none())
+            .map(|province| province.province_id())
+            .filter(|id| {
+                let bounces = resolved_orders
+                    .iter()
+                    .filter(|order| {
+                        order.order_type() == OrderType::Bounced
+                            && self.map.is_same_province(order.order_to(), *id)
+                    })
+                    .count();
+                bounces >= 2 && !self.map.is_province_occupied_including_coasts(*id)
+            })
+            .collect();
+
+        for id in 0..self.map.province_count() as ProvinceID {
+            let in_standoff = standoffs
+                .iter()
+                .any(|standoff| self.map.is_same_province(*standoff, id));
+            if let Some(province) = self.map.province_mut(id) {
+                province.set_available_for_retreat(!in_standoff);
+            }
+        }
+    }
+
+    // Resolves a retreat phase. Each dislodged unit retreats if it was given a legal retreat order, and is disbanded
+    // otherwise, or if another unit retreats to the same province. Returns the provinces that dislodged units were
+    // disbanded from.
+    pub fn resolve_retreats(&mut self, orders: &[RetreatOrder]) -> Vec<ProvinceID> {
+        let dislodged: Vec<ProvinceID> = self
+            .map
+            .dislodged_units()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+
+        let mut retreating_units = Vec::new();
+        let mut disbanded = Vec::new();
+        for from in dislodged {
+            let retreat = orders
+                .iter()
+                .find_map(|order| match order {
+                    RetreatOrder::Retreat { from: origin, to } if *origin == from => Some(*to),
+                    _ => None,
+                })
+                .filter(|to| self.is_legal_retreat(from, *to));
+
+            let Some(province) = self.map.province_mut(from) else {
+                continue;
+            };
+            let unit = province.take_disloged_unit();
+            province.set_dislodged_by(None);
+
+            match (retreat, unit) {
+                (Some(to), Some(unit)) => retreating_units.push((from, to, unit)),
+                _ => disbanded.push(from),
+            }
+        }
+
+        // Units retreating to the same province (counting its coasts) are all disbanded, leaving it empty.
+        let destinations: Vec<ProvinceID> = retreating_units.iter().map(|(_, to, _)| *to).collect();
+        for (from, to, unit) in retreating_units {
+            let collides = destinations
+                .iter()
+                .filter(|other| self.map.is_same_province(**other, to))
+                .count()
+                > 1;
+            if collides {
+                disbanded.push(from);
+            } else if let Some(province) = self.map.province_mut(to) {
+                province.set_occupant(Some(unit));
+            }
+        }
+
+        disbanded
+    }
+
+    // Resolves the adjustment orders for `player` in a winter phase, in the order they are given. Legal builds place a new
+    // unit, and legal disbands remove one; the rest are left out, and returned along with why they were rejected. Only one
+    // unit can be built in each center. The player's allowance is taken once, at the start of the winter: they may only
+    // build while they have builds left of it, and only disband while they still owe disbands, so any other builds or
+    // disbands are rejected, even if they are otherwise legal.
+    pub fn resolve_builds(&mut self, player: PlayerID, orders: &[BuildOrder]) -> BuildResult {
+        let mut result = BuildResult::default();
+        let mut allowance = self.build_allowance(player);
+        for order in orders {
+            self.resolve_build(player, order, &mut allowance, &mut result);
+        }
+        result
+    }
+
+    // `allowance` is what is left of the player's allowance for the winter, and goes towards zero with every unit built or
+    // disbanded.
+    fn resolve_build(
+        &mut self,
+        player: PlayerID,
+        order: &BuildOrder,
+        allowance: &mut i32,
+        result: &mut BuildResult,
+    ) {
+        // Checked first, as the center is now occupied by the unit that was built, which would be the reason given otherwise.
+        if let BuildOrder::Build { province, .. } = *order
+            && result
+                .built
+                .iter()
+                .any(|(built_in, _)| self.map.is_same_province(*built_in, province))
+        {
+            result
+                .rejected
+                .push((*order, OrderError::DuplicateBuild(province)));
+            return;
+        }
+
+        if let Err(error) = self.check_build(player, order) {
+            result.rejected.push((*order, error));
+            return;
+        }
+
+        match *order {
+            BuildOrder::Build {
+                province,
+                unit_type,
+                coast,
+            } => {
+                if *allowance <= 0 {
+                    result
+                        .rejected
+                        .push((*order, OrderError::NoBuildsLeft(province)));
+                    return;
+                }
+                *allowance -= 1;
+
+                let built_in = coast.unwrap_or(province);
+                if let Some(province) = self.map.province_mut(built_in) {
+                    province.set_occupant(Some(Unit::new(player, unit_type)));
+                    result.built.push((built_in, unit_type));
+                }
+            }
+            BuildOrder::Disband { province, .. } => {
+                if *allowance >= 0 {
+                    result
+                        .rejected
+                        .push((*order, OrderError::NoDisbandsLeft(province)));
+                    return;
+                }
+                *allowance += 1;
+
+                if let Some((occupied, _)) = self.map.occupant_including_coasts(province)
+                    && let Some(province) = self.map.province_mut(occupied)
+                {
+                    province.take_occupant();
+                    result.disbanded.push(occupied);
+                }
+            }
+            BuildOrder::Waive => result.waived += 1,
+        }
+    }
+
+    fn check_build(&self, player: PlayerID, order: &BuildOrder) -> Result<(), OrderError> {
+        match *order {
+            BuildOrder::Build {
+                province: province_id,
+                unit_type,
+                coast,
+            } => {
+                let province = self
+                    .map
+                    .province(province_id)
+                    .filter(|province| province.owned_by() == player)
+                    .filter(|_| self.map.home_centers(player).contains(&province_id))
+                    .ok_or(OrderError::NotOwnedHomeCenter(province_id))?;
+
+                if self.map.is_province_occupied_including_coasts(province_id) {
+                    return Err(OrderError::ProvinceOccupied(province_id));
+                }
+
+                // Land provinces can hold fleets, but only coastal ones can actually have fleets in them.
+                let inland = unit_type == UnitType::Fleet
+                    && coast.is_none()
+                    && self.map.neighbors(province_id, &unit_type).is_empty()
+                    && province.has_coasts().is_empty();
+                if !province.province_type().can_hold(&unit_type) || inland {
+                    return Err(OrderError::CannotHoldUnit(province_id));
+                }
+
+                match (unit_type, coast) {
+                    (UnitType::Fleet, None) if !province.has_coasts().is_empty() => {
+                        Err(OrderError::CoastRequired(province_id))
+                    }
+                    (UnitType::Fleet, Some(coast)) if province.has_coasts().contains(&coast) => {
+                        Ok(())
+                    }
+                    (_, Some(coast)) => Err(OrderError::InvalidCoast(coast)),
+                    (_, None) => Ok(()),
+                }
+            }
+            BuildOrder::Disband {
+                province,
+                unit_type,
+            } => match self.map.occupant_including_coasts(province) {
+                Some((_, unit)) if unit.owner() == player && unit.get_type() == unit_type => Ok(()),
+                _ => Err(OrderError::NoUnitToDisband(province)),
+            },
+            BuildOrder::Waive => Ok(()),
+        }
+    }
+
+    // A dislodged unit may retreat to any adjacent province that is empty, wasn't left empty by a standoff, and isn't where
+    // the unit that dislodged it came from.
+    fn is_legal_retreat(&self, from: ProvinceID, to: ProvinceID) -> bool {
+        let Some(province) = self.map.province(from) else {
+            return false;
+        };
+        let Some(unit) = province.disloged_unit() else {
+            return false;
+        };
+
+        self.map.neighbors(from, &unit.get_type()).contains(&to)
+            && !self.map.is_province_occupied_including_coasts(to)
+            && self
+                .map
+                .province(to)
+                .is_some_and(|destination| destination.available_for_retreat())
+            && province
+                .dislodged_by()
+                .is_none_or(|attacker_origin| !self.map.is_same_province(attacker_origin, to))
+    }
+
+    pub fn to_snapshot(&self) -> GameSnapshot {
+        let provinces = self
+            .map
+            .provinces()
+            .map(ProvinceSnapshot::from_province)
+            .collect();
+
+        let connections = self
+            .map
+            .connections()
+            .map(|connection| ConnectionSnapshot {
+                province_1_id: connection.province_1_id(),
+                province_2_id: connection.province_2_id(),
+                allowed_unit_types: connection.allowed_unit_types().to_vec(),
+            })
+            .collect();
+
+        let players = self
+            .players
+            .iter()
+            .map(|player| PlayerSnapshot {
+                player_id: player.player_id(),
+                player_name: player.player_name().to_string(),
+            })
+            .collect();
+
+        GameSnapshot {
+            config: self.config.clone(),
+            phase: self.phase,
+            year: self.year,
+            players,
+            provinces,
+            connections,
+        }
+    }
+
+    pub fn from_snapshot(snapshot: GameSnapshot) -> Result<Self, SnapshotError> {
+        let player_ids: Vec<PlayerID> = snapshot.players.iter().map(|p| p.player_id).collect();
+        // Neutral is always a valid owner, even though it isn't a player.
+        let known_player = |id: &PlayerID| *id == NEUTRAL || player_ids.contains(id);
+
+        let mut map = Map::new();
+        for (index, province) in snapshot.provinces.into_iter().enumerate() {
+            if province.province_id as usize != index {
+                return Err(SnapshotError::ProvinceOutOfOrder(province.province_id));
+            }
+            if !snapshot.config.is_enabled(&province.province_type) {
+                return Err(SnapshotError::DisabledProvinceType(province.province_id));
+            }
+            if let Some(parent) = province.is_coast_of
+                && parent >= province.province_id
+            {
+                return Err(SnapshotError::UnknownProvince(parent));
+            }
+            if let Some(player) = std::iter::once(&province.owned_by)
+                .chain(province.core_of.iter())
+                .chain(province.occupied_by.iter().map(|unit| &unit.owner))
+                .chain(province.disloged_unit.iter().map(|unit| &unit.owner))
+                .find(|player| !known_player(player))
+            {
+                return Err(SnapshotError::UnknownPlayer(*player));
+            }
+
+            map.add_province(province.into_province());
+        }
+
+        for connection in snapshot.connections {
+            let (id1, id2) = (connection.province_1_id, connection.province_2_id);
+            let new_connection = Connection::new(id1, id2, connection.allowed_unit_types)
+                .map_err(|_| SnapshotError::InvalidConnection(id1, id2))?;
+            if let Some(missing) = [id1, id2]
+                .into_iter()
+                .find(|id| map.province(*id).is_none())
+            {
+                return Err(SnapshotError::UnknownProvince(missing));
+            }
+
+            map.add_connection(new_connection);
+        }
+
+        let players = snapshot
+            .players
+            .into_iter()
+            .map(|player| Player::new(player.player_id, player.player_name))
+            .collect();
+
+        Ok(Self {
+            map,
+            players,
+            config: snapshot.config,
+            phase: snapshot.phase,
+            year: snapshot.year,
+            history: Vec::new(),
+            submissions: Vec::new(),
+        })
+    }
+}
+
+/// A single unit on the board. See `GameState::units`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnitView {
+    pub province: ProvinceID,
+
+    pub owner: PlayerID,
+
+    pub unit_type: UnitType,
+}
+
+/// The result of adjudicating a retreat phase with `GameState::adjudicate`.
+#[derive(Clone, Debug)]
+pub struct RetreatOutcome {
+    // The provinces that dislodged units were disbanded from (see `GameState::resolve_retreats`).
+    pub disbanded: Vec<ProvinceID>,
+
+    // The supply centers that changed hands once the fall retreats were over, as `(province, old owner, new owner)`. Always
+    // empty after a spring retreat phase.
+    pub sc_transfers: Vec<(ProvinceID, PlayerID, PlayerID)>,
+}
+
+/// The result of adjudicating a movement phase with `GameState::adjudicate`.
+#[derive(Clone, Debug)]
+pub struct Adjudication {
+    // Every order after resolution, including the holds added for units that weren't given an order.
+    pub outcomes: Vec<Order>,
+
+    // The supply centers that changed hands, as `(province, old owner, new owner)`. Supply centers only change hands once
+    // the fall is over, so this is always empty after a spring phase, and after a fall phase with retreats to make (the
+    // centers are given with the retreats instead, see `RetreatOutcome`).
+    pub sc_transfers: Vec<(ProvinceID, PlayerID, PlayerID)>,
+
+    // The provinces that units left without any other unit moving in.
+    pub vacated: Vec<ProvinceID>,
+
+    // The provinces that were empty before the phase and now have a unit in them, along with who owns it. A fleet that moved
+    // onto a coast is listed on that coast.
+    pub newly_occupied: Vec<(ProvinceID, PlayerID)>,
+
+    // Orders for provinces that had no unit in them, which were left out of the adjudication.
+    pub ignored: Vec<Order>,
+
+    // How much work resolving the orders took.
+    pub stats: ResolutionStats,
+}
+
+impl Adjudication {
+    // A printable report of the turn, with one line per order giving how it turned out, i.e. "A Par - Bur (bounced)". `map`
+    // should be the board as `GameState::adjudicate` left it, before any retreats, so that each unit can be found from where
+    // its order left it.
+    pub fn summary(&self, map: &Map) -> String {
+        self.outcomes
+            .iter()
+            .map(|order| {
+                let unit_type = self.starting_unit_type(order.order_of(), map);
+                let supported_type = self.starting_unit_type(order.order_from(), map);
+                format!(
+                    "{} ({})",
+                    order.format_with(map, unit_type, supported_type),
+                    order.outcome()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // The type of the unit that started the turn in `province`, found from where its order left it: in its destination if
+    // it moved, waiting to retreat if it was dislodged, and where it was otherwise.
+    fn starting_unit_type(&self, province: ProvinceID, map: &Map) -> Option<UnitType> {
+        let order = order::order_for(&self.outcomes, province)?;
+        let unit = if order.is_dislodged() {
+            map.province(province)?.disloged_unit()
+        } else if order.order_type() == OrderType::Move {
+            map.occupant_including_coasts(order.order_to())
+                .map(|(_, unit)| unit)
+        } else {
+            map.occupant_including_coasts(province)
+                .map(|(_, unit)| unit)
+        };
+        unit.map(|unit| unit.get_type())
+    }
+}
+
+/// A single adjudicated phase, as recorded in `GameState::history`.
+#[derive(Clone, Debug)]
+pub struct TurnRecord {
+    pub phase: Phase,
+
+    pub year: u16,
+
+    // The whole game as it was just before the phase was adjudicated, for going back to it (see `GameState::rewind`).
+    pub before: GameSnapshot,
+
+    // The orders as they were given.
+    pub orders: PhaseOrders,
+
+    // How the phase turned out, the same as `GameState::adjudicate` returned.
+    pub outcome: PhaseOutcome,
+}
+
+/// Plain-data view of a `GameState`, suitable for saving and loading games.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub config: VariantConfig,
+    pub phase: Phase,
+    pub year: u16,
+    pub players: Vec<PlayerSnapshot>,
+    pub provinces: Vec<ProvinceSnapshot>,
+    pub connections: Vec<ConnectionSnapshot>,
+}
+
+// The version of the format written by `GameSnapshot::to_bytes`. Bump this whenever the snapshot types change.
+#[cfg(feature = "bincode")]
+const SNAPSHOT_FORMAT_VERSION: u8 = 1;
+
+#[cfg(feature = "bincode")]
+impl GameSnapshot {
+    // A compact binary encoding of the snapshot, for archiving many games. The first byte is the format version, so that
+    // bytes from other versions of the format can be told apart.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![SNAPSHOT_FORMAT_VERSION];
+        // Writing into a `Vec` can't fail, and with no size limit set bincode only refuses a sequence of unknown length
+        // or a hand-written `Serialize` that returns an error. Everything in a snapshot is derived, so this never panics.
+        bytes.extend(bincode::serialize(self).expect("Snapshots can always be serialized!"));
+        bytes
+    }
+
+    // Reads a snapshot written by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        match bytes.split_first() {
+            Some((&SNAPSHOT_FORMAT_VERSION, rest)) => {
+                bincode::deserialize(rest).map_err(|_| SnapshotError::InvalidBytes)
+            }
+            Some((version, _)) => Err(SnapshotError::UnsupportedVersion(*version)),
+            None => Err(SnapshotError::InvalidBytes),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub player_id: PlayerID,
+    pub player_name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UnitSnapshot {
+    pub owner: PlayerID,
+    pub unit_type: UnitType,
+}
+
+impl UnitSnapshot {
+    fn from_unit(unit: &Unit) -> Self {
+        Self {
+            owner: unit.owner(),
+            unit_type: unit.get_type(),
+        }
+    }
+
+    fn into_unit(self) -> Unit {
+        Unit::new(self.owner, self.unit_type)
+    }
+}
+
+// Coasts are rebuilt from `is_coast_of` when loading, so `has_coasts` isn't stored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProvinceSnapshot {
+    pub province_id: ProvinceID,
+    pub province_name: String,
+    pub province_type: ProvinceType,
+    pub owned_by: PlayerID,
+    pub sc_value: Option<u8>,
+    pub core_of: Vec<PlayerID>,
+    pub is_coast_of: Option<ProvinceID>,
+    pub occupied_by: Option<UnitSnapshot>,
+    pub disloged_unit: Option<UnitSnapshot>,
+    pub dislodged_by: Option<ProvinceID>,
+    pub available_for_retreat: bool,
+}
+
+impl ProvinceSnapshot {
+    fn from_province(province: &Province) -> Self {
+        Self {
+            province_id: province.province_id(),
+            province_name: province.province_name().to_string(),
+            province_type: province.province_type(),
+            owned_by: province.owned_by(),
+            sc_value: province.sc_value(),
+            core_of: province.core_of().to_vec(),
+            is_coast_of: province.is_coast_of(),
+            occupied_by: province.occupied_by().map(UnitSnapshot::from_unit),
+            disloged_unit: province.disloged_unit().map(UnitSnapshot::from_unit),
+            dislodged_by: province.dislodged_by(),
+            available_for_retreat: province.available_for_retreat(),
+        }
+    }
+
+    fn into_province(self) -> Province {
+        let mut province = Province::new(self.province_id, self.province_name, self.province_type);
+        province.set_owner(self.owned_by);
+        province.set_sc_value(self.sc_value);
+        for player in self.core_of {
+            province.add_core(player);
+        }
+        if let Some(parent) = self.is_coast_of {
+            province.set_coast_of(parent);
+        }
+        province.set_occupant(self.occupied_by.map(UnitSnapshot::into_unit));
+        province.set_disloged_unit(self.disloged_unit.map(UnitSnapshot::into_unit));
+        province.set_dislodged_by(self.dislodged_by);
+        province.set_available_for_retreat(self.available_for_retreat);
+        province
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionSnapshot {
+    pub province_1_id: ProvinceID,
+    pub province_2_id: ProvinceID,
+    pub allowed_unit_types: Vec<UnitType>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MapError;
+
+    // A game on the standard map, at the start of Spring 1901.
+    fn standard_game() -> GameState {
+        GameState::new(
+            Map::standard(),
+            Player::standard_powers(),
+            VariantConfig::standard(),
+        )
+        .unwrap()
+    }
+
+    fn id(game: &GameState, name: &str) -> ProvinceID {
+        game.map()
+            .province_by_name(name)
+            .unwrap_or_else(|| panic!("{name} should be on the map"))
+    }
+
+    fn orders(game: &GameState, orders: &[&str]) -> Vec<Order> {
+        orders
+            .iter()
+            .map(|order| Order::parse(order, game.map()).unwrap())
+            .collect()
+    }
+
+    // Adjudicates a movement phase with the given orders, returning the outcome.
+    fn move_units(game: &mut GameState, given: &[&str]) -> Adjudication {
+        let given = orders(game, given);
+        match game.adjudicate(PhaseOrders::Movement(given)) {
+            Ok(PhaseOutcome::Movement(adjudication)) => adjudication,
+            other => panic!("expected a movement outcome, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trips_a_game_in_progress() {
+        let mut game = standard_game();
+        move_units(&mut game, &["A Par - Bur", "F Bre - MAO", "F StP/sc - BOT"]);
+
+        let snapshot = game.to_snapshot();
+        let restored = GameState::from_snapshot(snapshot.clone()).unwrap();
+
+        assert_eq!(restored.to_snapshot(), snapshot);
+        assert_eq!(restored.phase(), Phase::FallMovement);
+        assert_eq!(restored.year(), 1901);
+        assert_eq!(restored.map().occupant_owner(id(&game, "Bur")), Some(3));
+        assert_eq!(restored.map().occupant_owner(id(&game, "Par")), None);
+        assert_eq!(
+            restored
+                .map()
+                .province(id(&game, "StP"))
+                .unwrap()
+                .has_coasts(),
+            &[id(&game, "StP/nc"), id(&game, "StP/sc")]
+        );
+    }
+
+    #[test]
+    fn snapshot_with_an_unknown_player_is_rejected() {
+        let mut snapshot = standard_game().to_snapshot();
+        snapshot
+            .players
+            .retain(|player| player.player_name != "France");
+
+        assert_eq!(
+            GameState::from_snapshot(snapshot).err(),
+            Some(SnapshotError::UnknownPlayer(3))
+        );
+    }
+
+    #[test]
+    fn apply_movements_moves_units_and_dislodges_defenders() {
+        let mut game = standard_game();
+        let bur = id(&game, "Bur");
+        game.place_units(vec![(bur, 4, UnitType::Army)]).unwrap();
+        let given = orders(&game, &["A Par - Bur", "A Mar S A Par - Bur", "A Bur H"]);
+
+        let resolved = order::resolve_orders(&given, game.map(), Default::default()).unwrap();
+        // Resolving on its own leaves the board as it was.
+        assert_eq!(game.map().occupant_owner(bur), Some(4));
+
+        game.apply_movements(&resolved);
+        let burgundy = game.map().province(bur).unwrap();
+        assert_eq!(burgundy.occupied_by(), Some(&Unit::new(3, UnitType::Army)));
+        assert_eq!(
+            burgundy.disloged_unit(),
+            Some(&Unit::new(4, UnitType::Army))
+        );
+        assert_eq!(burgundy.dislodged_by(), Some(id(&game, "Par")));
+        assert_eq!(game.map().occupant_owner(id(&game, "Par")), None);
+        assert_eq!(game.map().occupant_owner(id(&game, "Mar")), Some(3));
+    }
+
+    #[test]
+    fn winner_uses_the_variant_win_threshold() {
+        let mut map = Map::standard();
+        let give_to_france = |map: &mut Map, names: &[&str]| {
+            for name in names {
+                let province = map.province_by_name(name).unwrap();
+                map.province_mut(province).unwrap().set_owner(3);
+            }
+        };
+        // France starts with Bre, Mar and Par.
+        give_to_france(
+            &mut map,
+            &["Spa", "Por", "Bel", "Hol", "Mun", "Kie", "Ber", "Lon"],
+        );
+        let twelve = VariantConfig::new(
+            12,
+            1901,
+            vec![ProvinceType::Land, ProvinceType::Coast, ProvinceType::Water],
+        );
+
+        let game = GameState::new(map.clone(), Player::standard_powers(), twelve.clone()).unwrap();
+        assert_eq!(game.supply_center_count(3), 11);
+        assert_eq!(game.winner(), None);
+
+        give_to_france(&mut map, &["Lvp"]);
+        let game = GameState::new(map.clone(), Player::standard_powers(), twelve).unwrap();
+        assert_eq!(game.winner(), Some(3));
+
+        // The same board is nowhere near a win in the standard game.
+        let game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+        assert_eq!(game.winner(), None);
+    }
+
+    #[test]
+    fn new_game_rejects_a_province_type_the_variant_does_not_allow() {
+        let map = Map::standard();
+        let first_sea = map
+            .provinces()
+            .find(|province| province.province_type() == ProvinceType::Water)
+            .unwrap()
+            .province_id();
+        let landlocked =
+            VariantConfig::new(18, 1901, vec![ProvinceType::Land, ProvinceType::Coast]);
+
+        assert_eq!(
+            GameState::new(map, Player::standard_powers(), landlocked).err(),
+            Some(SetupError::DisabledProvinceType(first_sea))
+        );
+    }
+
+    #[test]
+    fn neutral_supply_centers_count_for_no_player() {
+        let game = standard_game();
+        let neutral: Vec<&Province> = game
+            .map()
+            .provinces()
+            .filter(|province| province.is_supply_center() && province.is_neutral())
+            .collect();
+        let owned: u8 = game
+            .players()
+            .iter()
+            .map(|player| game.supply_center_count(player.player_id()))
+            .sum();
+
+        // The 12 neutral centers, i.e. Belgium, are left out of every player's count.
+        assert_eq!(neutral.len(), 12);
+        assert!(
+            neutral
+                .iter()
+                .any(|province| province.province_name() == "Bel")
+        );
+        assert_eq!(owned, 22);
+        assert_eq!(game.supply_center_count(3), 3);
+        assert_eq!(game.supply_center_count(6), 4);
+        assert_eq!(game.supply_center_count(NEUTRAL), 0);
+    }
+
+    // A Spring 1901 game where Germany's army in Burgundy has just been dislodged by France.
+    fn game_with_a_dislodged_army() -> GameState {
+        let mut game = standard_game();
+        let bur = id(&game, "Bur");
+        game.place_units(vec![(bur, 4, UnitType::Army)]).unwrap();
+        move_units(
+            &mut game,
+            &["A Par - Bur", "A Mar S A Par - Bur", "A Bur H"],
+        );
+        assert_eq!(game.phase(), Phase::SpringRetreat);
+        game
+    }
+
+    fn retreat(game: &mut GameState, order: &str) -> Vec<ProvinceID> {
+        let order = RetreatOrder::parse(order, game.map()).unwrap();
+        match game.adjudicate(PhaseOrders::Retreat(vec![order])) {
+            Ok(PhaseOutcome::Retreat(outcome)) => outcome.disbanded,
+            other => panic!("expected a retreat outcome, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn dislodged_unit_retreats_to_an_empty_province() {
+        let mut game = game_with_a_dislodged_army();
+
+        assert!(retreat(&mut game, "A Bur - Ruh").is_empty());
+        assert_eq!(game.map().occupant_owner(id(&game, "Ruh")), Some(4));
+        assert_eq!(game.map().occupant_owner(id(&game, "Bur")), Some(3));
+        assert_eq!(game.phase(), Phase::FallMovement);
+    }
+
+    #[test]
+    fn retreat_to_an_occupied_province_disbands_the_unit() {
+        let mut game = game_with_a_dislodged_army();
+
+        // France's supporting army is still in Marseilles.
+        assert_eq!(retreat(&mut game, "A Bur - Mar"), vec![id(&game, "Bur")]);
+        assert_eq!(game.map().occupant_owner(id(&game, "Mar")), Some(3));
+        assert_eq!(game.unit_count(4), 3);
+    }
+
+    // A game on the standard map with only France's three starting units on the board.
+    fn french_game() -> GameState {
+        let mut map = Map::standard();
+        let others: Vec<ProvinceID> = map
+            .occupied()
+            .filter(|(_, unit)| unit.owner() != 3)
+            .map(|(id, _)| id)
+            .collect();
+        for id in others {
+            map.province_mut(id).unwrap().set_occupant(None);
+        }
+        GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap()
+    }
+
+    #[test]
+    fn unordered_units_are_given_holds() {
+        let game = french_game();
+        let filled = game.fill_missing_with_holds(orders(&game, &["A Par - Bur"]));
+
+        assert_eq!(filled.len(), 3);
+        assert_eq!(filled[0].order_type(), OrderType::Move);
+        let mut holding: Vec<ProvinceID> = filled[1..]
+            .iter()
+            .filter(|order| order.order_type() == OrderType::Hold)
+            .map(|order| order.order_of())
+            .collect();
+        holding.sort();
+        let mut expected = vec![id(&game, "Bre"), id(&game, "Mar")];
+        expected.sort();
+        assert_eq!(holding, expected);
+    }
+
+    #[test]
+    fn order_without_a_coast_is_for_the_fleet_on_the_coast() {
+        let mut game = french_game();
+        let spa_nc = id(&game, "Spa/nc");
+        game.place_units(vec![(spa_nc, 3, UnitType::Fleet)])
+            .unwrap();
+
+        let given = orders(&game, &["F Spa - MAO"]);
+        assert_eq!(given[0].order_of(), spa_nc);
+        assert_eq!(game.fill_missing_with_holds(given).len(), 4);
+
+        let adjudication = move_units(&mut game, &["F Spa - MAO"]);
+        assert_eq!(adjudication.outcomes.len(), 4);
+        assert_eq!(game.map().occupant_owner(id(&game, "MAO")), Some(3));
+        assert!(!game.map().is_province_occupied_including_coasts(spa_nc));
+    }
+
+    #[test]
+    fn three_way_standoff_leaves_the_province_empty_and_closed_to_retreats() {
+        let mut game = standard_game();
+        let (pic, bel) = (id(&game, "Pic"), id(&game, "Bel"));
+        game.place_units(vec![(pic, 4, UnitType::Army), (bel, 3, UnitType::Army)])
+            .unwrap();
+        let adjudication = move_units(
+            &mut game,
+            &[
+                "A Par - Bur",
+                "A Mar - Bur",
+                "A Mun - Bur",
+                "F Bre - Pic",
+                "A Bel S F Bre - Pic",
+            ],
+        );
+        let bur = id(&game, "Bur");
+
+        for from in ["Par", "Mar", "Mun"] {
+            let order = order::order_for(&adjudication.outcomes, id(&game, from)).unwrap();
+            assert_eq!(order.outcome(), "bounced", "{from}");
+        }
+        assert!(!game.map().is_province_occupied_including_coasts(bur));
+        assert_eq!(game.map().occupant_owner(id(&game, "Mun")), Some(4));
+
+        // Germany's army dislodged from Picardy can't retreat into the standoff.
+        assert_eq!(game.phase(), Phase::SpringRetreat);
+        assert!(!game.map().province(bur).unwrap().available_for_retreat());
+        assert!(!game.is_legal_retreat(pic, bur));
+    }
+
+    // A game on the standard map where Russia's units in St. Petersburg and Moscow are gone, leaving Russia two builds.
+    fn russian_builds_game() -> GameState {
+        let mut map = Map::standard();
+        for name in ["StP/sc", "Mos"] {
+            let province = map.province_by_name(name).unwrap();
+            map.province_mut(province).unwrap().set_occupant(None);
+        }
+        GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap()
+    }
+
+    fn build(game: &mut GameState, order: &str) -> BuildResult {
+        let order = BuildOrder::parse(order, game.map()).unwrap();
+        game.resolve_builds(6, &[order])
+    }
+
+    #[test]
+    fn fleet_is_built_on_the_coast_it_names() {
+        let mut game = russian_builds_game();
+        let stp_nc = id(&game, "StP/nc");
+
+        let result = build(&mut game, "Build F StP/nc");
+        assert_eq!(result.built, vec![(stp_nc, UnitType::Fleet)]);
+        assert!(result.rejected.is_empty());
+        assert_eq!(
+            game.map().province(stp_nc).unwrap().occupied_by(),
+            Some(&Unit::new(6, UnitType::Fleet))
+        );
+    }
+
+    #[test]
+    fn fleet_built_in_a_split_coast_center_needs_a_coast() {
+        let mut game = russian_builds_game();
+        let stp = id(&game, "StP");
+
+        let result = build(&mut game, "Build F StP");
+        assert!(result.built.is_empty());
+        assert_eq!(result.rejected[0].1, OrderError::CoastRequired(stp));
+        assert!(!game.map().is_province_occupied_including_coasts(stp));
+    }
+
+    #[test]
+    fn army_cannot_be_built_on_a_coast() {
+        let mut game = russian_builds_game();
+
+        let result = build(&mut game, "Build A StP/nc");
+        assert!(result.built.is_empty());
+        assert_eq!(
+            result.rejected[0].1,
+            OrderError::InvalidCoast(id(&game, "StP/nc"))
+        );
+
+        // An army can still be built in the province itself.
+        assert_eq!(build(&mut game, "Build A StP").built.len(), 1);
+    }
+
+    #[test]
+    fn history_records_each_adjudicated_turn() {
+        let mut game = standard_game();
+        move_units(&mut game, &["A Par - Bur"]);
+        move_units(&mut game, &["A Bur - Mun", "A Mun H"]);
+
+        let history = game.history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            (history[0].phase, history[0].year),
+            (Phase::SpringMovement, 1901)
+        );
+        assert_eq!(
+            (history[1].phase, history[1].year),
+            (Phase::FallMovement, 1901)
+        );
+
+        // The orders are kept as given, and the outcomes include the holds added for every other unit.
+        let (PhaseOrders::Movement(given), PhaseOutcome::Movement(adjudication)) =
+            (&history[1].orders, &history[1].outcome)
+        else {
+            panic!("expected a movement record, got {:?}", history[1]);
+        };
+        assert_eq!(given.len(), 2);
+        assert_eq!(adjudication.outcomes.len(), 22);
+        let bur = order::order_for(&adjudication.outcomes, id(&game, "Bur")).unwrap();
+        assert_eq!(bur.outcome(), "bounced");
+    }
+
+    #[test]
+    fn player_cannot_order_another_players_unit() {
+        let mut game = standard_game();
+        let set = OrderSet::new(3, orders(&game, &["A Par - Bur", "A Mun - Bur"]));
+
+        assert_eq!(
+            game.submit(set),
+            Err(vec![OrderError::NotOwnUnit(id(&game, "Mun"))])
+        );
+        assert!(game.submitted_orders().is_empty());
+    }
+
+    #[test]
+    fn traced_adjudication_gives_the_steps_of_a_movement_phase() {
+        let mut game = standard_game();
+        let given = orders(&game, &["A Par - Bur", "A Mun - Bur"]);
+        let (outcome, trace) = game
+            .adjudicate_traced(PhaseOrders::Movement(given))
+            .unwrap();
+        let PhaseOutcome::Movement(adjudication) = outcome else {
+            panic!("expected a movement outcome, got {outcome:?}");
+        };
+        let bur = id(&game, "Bur");
+        assert_eq!(
+            order::order_for(&adjudication.outcomes, id(&game, "Par"))
+                .unwrap()
+                .outcome(),
+            "bounced"
+        );
+        assert!(trace.contains(&ResolutionStep::MoveBounced {
+            from: id(&game, "Par"),
+            to: bur
+        }));
+        assert!(trace.contains(&ResolutionStep::MoveBounced {
+            from: id(&game, "Mun"),
+            to: bur
+        }));
+
+        // A retreat phase has no decisions to trace.
+        let mut game = game_with_a_dislodged_army();
+        let order = RetreatOrder::parse("A Bur - Ruh", game.map()).unwrap();
+        let (outcome, trace) = game
+            .adjudicate_traced(PhaseOrders::Retreat(vec![order]))
+            .unwrap();
+        assert!(matches!(outcome, PhaseOutcome::Retreat(_)));
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn order_for_an_empty_province_is_left_out_and_reported() {
+        let mut game = standard_game();
+        // There is no army in Picardy to bounce the one from Paris.
+        let adjudication = move_units(&mut game, &["A Par - Bur", "A Pic - Bur"]);
+
+        assert_eq!(adjudication.ignored.len(), 1);
+        assert_eq!(adjudication.ignored[0].order_of(), id(&game, "Pic"));
+        assert!(order::order_for(&adjudication.outcomes, id(&game, "Pic")).is_none());
+        assert_eq!(
+            order::order_for(&adjudication.outcomes, id(&game, "Par"))
+                .unwrap()
+                .outcome(),
+            "success"
+        );
+        assert_eq!(game.map().occupant_owner(id(&game, "Bur")), Some(3));
+        assert_eq!(game.map().occupant_owner(id(&game, "Pic")), None);
+    }
+
+    #[test]
+    fn units_lists_every_unit_on_the_board_but_not_those_retreating() {
+        let game = standard_game();
+        let units = game.units();
+        assert_eq!(units.len(), 22);
+        assert!(
+            units
+                .windows(2)
+                .all(|pair| pair[0].province < pair[1].province)
+        );
+        assert!(units.contains(&UnitView {
+            province: id(&game, "Par"),
+            owner: 3,
+            unit_type: UnitType::Army,
+        }));
+        assert!(units.contains(&UnitView {
+            province: id(&game, "StP/sc"),
+            owner: 6,
+            unit_type: UnitType::Fleet,
+        }));
+        assert!(!units.iter().any(|unit| unit.province == id(&game, "StP")));
+
+        // The German army dislodged from Burgundy isn't on the board until it retreats.
+        let game = game_with_a_dislodged_army();
+        let bur: Vec<UnitView> = game
+            .units()
+            .into_iter()
+            .filter(|unit| unit.province == id(&game, "Bur"))
+            .collect();
+        assert_eq!(
+            bur,
+            vec![UnitView {
+                province: id(&game, "Bur"),
+                owner: 3,
+                unit_type: UnitType::Army,
+            }]
+        );
+    }
+
+    #[test]
+    fn movement_reports_provinces_vacated_and_newly_occupied() {
+        let mut game = standard_game();
+        // The fleet leaving St Petersburg's south coast vacates it as well.
+        let adjudication = move_units(&mut game, &["A Par - Bur", "F StP/sc - BOT", "A Mar - Gas"]);
+
+        let mut vacated = adjudication.vacated.clone();
+        vacated.sort();
+        let mut expected = vec![id(&game, "Par"), id(&game, "StP/sc"), id(&game, "Mar")];
+        expected.sort();
+        assert_eq!(vacated, expected);
+
+        let mut newly_occupied = adjudication.newly_occupied.clone();
+        newly_occupied.sort();
+        let mut expected = vec![
+            (id(&game, "Bur"), 3),
+            (id(&game, "BOT"), 6),
+            (id(&game, "Gas"), 3),
+        ];
+        expected.sort();
+        assert_eq!(newly_occupied, expected);
+    }
+
+    #[test]
+    fn province_left_and_entered_in_the_same_turn_is_neither_vacated_nor_newly_occupied() {
+        let mut game = standard_game();
+        let gas = id(&game, "Gas");
+        game.place_units(vec![(gas, 3, UnitType::Army)]).unwrap();
+        let adjudication = move_units(&mut game, &["A Par - Pic", "A Gas - Par"]);
+
+        assert!(!adjudication.vacated.contains(&id(&game, "Par")));
+        assert!(
+            !adjudication
+                .newly_occupied
+                .iter()
+                .any(|(province, _)| *province == id(&game, "Par"))
+        );
+        assert_eq!(adjudication.vacated, vec![gas]);
+    }
+
+    #[test]
+    fn phases_are_written_out_with_or_without_the_year() {
+        assert_eq!(Phase::SpringMovement.to_string(), "Spring Movement");
+        assert_eq!(Phase::FallRetreat.to_string(), "Fall Retreat");
+        assert_eq!(Phase::WinterBuild.describe(1901), "Winter 1901 Build");
+        assert_eq!(
+            AdjudicationError::WrongPhase(Phase::SpringRetreat).to_string(),
+            "those orders can't be adjudicated in Spring Retreat"
+        );
+    }
+
+    #[test]
+    fn reachable_targets_are_the_centers_one_move_away() {
+        let mut game = standard_game();
+        let targets = |game: &GameState, player: PlayerID| {
+            let mut names: Vec<&str> = game
+                .reachable_targets(player)
+                .into_iter()
+                .map(|province| game.map().province(province).unwrap().province_name())
+                .collect();
+            names.sort();
+            names.join(" ")
+        };
+
+        // At the start, England's units are too far from any center they don't own.
+        assert_eq!(targets(&game, 2), "");
+        assert_eq!(targets(&game, 3), "Spa");
+        assert_eq!(targets(&game, 4), "Den Hol");
+        assert_eq!(targets(&game, 7), "Bul");
+
+        // A fleet in the North Sea can take any of the centers around it, and so could an army convoyed by it. The Turkish
+        // fleet in the Black Sea reaches Bulgaria through its east coast.
+        move_units(&mut game, &["F Lon - NTH", "A Lvp - Yor", "F Ank - BLA"]);
+        assert_eq!(targets(&game, 2), "Bel Den Hol Nwy");
+        assert_eq!(targets(&game, 7), "Bul Rum Sev");
+    }
+
+    #[test]
+    fn nothing_from_one_turn_carries_into_the_next() {
+        let mut game = standard_game();
+        let (bur, tyr) = (id(&game, "Bur"), id(&game, "Tyr"));
+        game.place_units(vec![(bur, 4, UnitType::Army)]).unwrap();
+
+        // Spring: Germany is dislodged from Burgundy, and Germany and Italy stand off in Tyrolia.
+        move_units(
+            &mut game,
+            &[
+                "A Par - Bur",
+                "A Mar S A Par - Bur",
+                "A Bur H",
+                "A Mun - Tyr",
+                "A Ven - Tyr",
+            ],
+        );
+        assert_eq!(
+            game.map().province(bur).unwrap().dislodged_by(),
+            Some(id(&game, "Par"))
+        );
+        assert!(!game.map().province(tyr).unwrap().available_for_retreat());
+
+        // The German army doesn't retreat, so is disbanded.
+        assert_eq!(retreat(&mut game, "A Bur D"), vec![bur]);
+        assert_eq!(game.phase(), Phase::FallMovement);
+        let burgundy = game.map().province(bur).unwrap();
+        assert_eq!(burgundy.disloged_unit(), None);
+        assert_eq!(burgundy.dislodged_by(), None);
+        assert!(game.map().province(tyr).unwrap().available_for_retreat());
+
+        // Fall: a move into Tyrolia goes ahead as normal, and nobody is dislodged.
+        let adjudication = move_units(&mut game, &["A Mun - Tyr"]);
+        assert_eq!(
+            order::order_for(&adjudication.outcomes, id(&game, "Mun"))
+                .unwrap()
+                .outcome(),
+            "success"
+        );
+        assert!(game.map().dislodged_units().is_empty());
+        assert!(
+            game.map()
+                .provinces()
+                .all(|province| province.available_for_retreat())
+        );
+    }
+
+    #[test]
+    fn unit_given_two_orders_is_rejected() {
+        let mut game = standard_game();
+        let set = OrderSet::new(
+            6,
+            orders(
+                &game,
+                &["A Mos - Ukr", "F StP/sc - BOT", "F StP/sc H", "A Mos H"],
+            ),
+        );
+        assert_eq!(
+            game.submit(set),
+            Err(vec![
+                OrderError::DuplicateOrder(id(&game, "StP/sc")),
+                OrderError::DuplicateOrder(id(&game, "Mos")),
+            ])
+        );
+        assert!(game.submitted_orders().is_empty());
+
+        // Orders passed straight to adjudication are checked as well, and the board is left as it was.
+        let given = orders(&game, &["A Par - Bur", "A Par - Pic"]);
+        assert_eq!(
+            game.adjudicate(PhaseOrders::Movement(given)).err(),
+            Some(AdjudicationError::InvalidOrder(OrderError::DuplicateOrder(
+                id(&game, "Par")
+            )))
+        );
+        assert_eq!(game.phase(), Phase::SpringMovement);
+        assert_eq!(game.map().occupant_owner(id(&game, "Par")), Some(3));
+    }
+
+    #[test]
+    fn submitted_orders_from_every_player_are_merged() {
+        let mut game = standard_game();
+        game.submit(OrderSet::new(3, orders(&game, &["A Par - Bur"])))
+            .unwrap();
+        game.submit(OrderSet::new(4, orders(&game, &["A Mun - Bur"])))
+            .unwrap();
+        // A second set from the same player replaces the first.
+        game.submit(OrderSet::new(3, orders(&game, &["A Par - Pic"])))
+            .unwrap();
+
+        let submitted = game.submitted_orders();
+        assert_eq!(submitted.len(), 2);
+        assert!(
+            submitted
+                .iter()
+                .any(|order| order.order_to() == id(&game, "Pic"))
+        );
+        assert!(
+            submitted
+                .iter()
+                .any(|order| order.order_of() == id(&game, "Mun"))
+        );
+    }
+
+    #[test]
+    fn standoff_only_lasts_for_the_turn_it_happened_in() {
+        let mut game = standard_game();
+        let (pic, bur) = (id(&game, "Pic"), id(&game, "Bur"));
+        game.place_units(vec![
+            (pic, 4, UnitType::Army),
+            (id(&game, "Bel"), 3, UnitType::Army),
+        ])
+        .unwrap();
+        move_units(
+            &mut game,
+            &[
+                "A Par - Bur",
+                "A Mun - Bur",
+                "F Bre - Pic",
+                "A Bel S F Bre - Pic",
+            ],
+        );
+        assert!(!game.map().province(bur).unwrap().available_for_retreat());
+
+        retreat(&mut game, "A Pic D");
+        move_units(&mut game, &[]);
+        assert!(game.map().province(bur).unwrap().available_for_retreat());
+    }
+
+    #[test]
+    fn build_result_sorts_each_adjustment_into_what_happened() {
+        // France has three centers and two units, so can build one unit in either Paris or Marseilles. Italy has lost
+        // Naples, so has one unit too many.
+        let mut map = Map::standard();
+        for name in ["Par", "Mar"] {
+            let province = map.province_by_name(name).unwrap();
+            map.province_mut(province).unwrap().set_occupant(None);
+        }
+        let burgundy = map.province_by_name("Bur").unwrap();
+        map.province_mut(burgundy)
+            .unwrap()
+            .set_occupant(Some(Unit::new(3, UnitType::Army)));
+        let naples = map.province_by_name("Nap").unwrap();
+        map.province_mut(naples).unwrap().set_owner(NEUTRAL);
+        let mut game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+        let (par, mar) = (id(&game, "Par"), id(&game, "Mar"));
+
+        let parse = |game: &GameState, order: &str| BuildOrder::parse(order, game.map()).unwrap();
+        let over_quota = parse(&game, "Build A Mar");
+        let french = game.resolve_builds(
+            3,
+            &[
+                parse(&game, "Build A Par"),
+                over_quota,
+                parse(&game, "Waive"),
+            ],
+        );
+        assert_eq!(
+            french,
+            BuildResult {
+                built: vec![(par, UnitType::Army)],
+                disbanded: Vec::new(),
+                waived: 1,
+                rejected: vec![(over_quota, OrderError::NoBuildsLeft(mar))],
+            }
+        );
+
+        let italian = game.resolve_builds(5, &[parse(&game, "Disband F Nap")]);
+        assert_eq!(
+            italian,
+            BuildResult {
+                disbanded: vec![naples],
+                ..BuildResult::default()
+            }
+        );
+        assert_eq!(game.map().occupant_owner(naples), None);
+    }
+
+    // A standard game with the units in `emptied` taken off the board and the given units put on it instead.
+    fn game_with_units_moved(emptied: &[&str], placed: &[(&str, PlayerID)]) -> GameState {
+        let mut map = Map::standard();
+        for name in emptied {
+            let province = map.province_by_name(name).unwrap();
+            map.province_mut(province).unwrap().set_occupant(None);
+        }
+        for (name, owner) in placed {
+            let province = map.province_by_name(name).unwrap();
+            map.province_mut(province)
+                .unwrap()
+                .set_occupant(Some(Unit::new(*owner, UnitType::Army)));
+        }
+        GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap()
+    }
+
+    #[test]
+    fn only_one_unit_is_built_in_each_center() {
+        let mut game = game_with_units_moved(&["Par", "Mar"], &[]);
+        let par = id(&game, "Par");
+        let army = BuildOrder::parse("Build A Par", game.map()).unwrap();
+
+        let result = game.resolve_builds(3, &[army, army]);
+        assert_eq!(result.built, vec![(par, UnitType::Army)]);
+        assert_eq!(
+            result.rejected,
+            vec![(army, OrderError::DuplicateBuild(par))]
+        );
+    }
+
+    #[test]
+    fn builds_beyond_the_allowance_are_rejected() {
+        // Russia has four centers and two units, with three of its home centers empty.
+        let mut game = game_with_units_moved(&["StP/sc", "Mos", "Sev"], &[("Ukr", 6)]);
+        assert_eq!(game.build_allowance(6), 2);
+        let builds: Vec<BuildOrder> = ["Build A Mos", "Build F Sev", "Build A StP"]
+            .iter()
+            .map(|order| BuildOrder::parse(order, game.map()).unwrap())
+            .collect();
+
+        let result = game.resolve_builds(6, &builds);
+        assert_eq!(
+            result.built,
+            vec![
+                (id(&game, "Mos"), UnitType::Army),
+                (id(&game, "Sev"), UnitType::Fleet)
+            ]
+        );
+        assert_eq!(
+            result.rejected,
+            vec![(builds[2], OrderError::NoBuildsLeft(id(&game, "StP")))]
+        );
+        assert_eq!(game.build_allowance(6), 0);
+    }
+
+    #[test]
+    fn disbands_do_not_make_room_for_more_builds() {
+        // France has three centers and two units, so has one build and no disbands.
+        let mut game = game_with_units_moved(&["Par"], &[]);
+        let orders: Vec<BuildOrder> = [
+            "Disband A Mar",
+            "Disband F Bre",
+            "Build A Par",
+            "Build A Mar",
+        ]
+        .iter()
+        .map(|order| BuildOrder::parse(order, game.map()).unwrap())
+        .collect();
+        let (mar, bre) = (id(&game, "Mar"), id(&game, "Bre"));
+
+        let result = game.resolve_builds(3, &orders);
+        assert_eq!(result.built, vec![(id(&game, "Par"), UnitType::Army)]);
+        assert!(result.disbanded.is_empty());
+        assert_eq!(
+            result.rejected,
+            vec![
+                (orders[0], OrderError::NoDisbandsLeft(mar)),
+                (orders[1], OrderError::NoDisbandsLeft(bre)),
+                (orders[3], OrderError::ProvinceOccupied(mar)),
+            ]
+        );
+        assert_eq!(game.unit_count(3), 3);
+    }
+
+    #[test]
+    fn disbands_beyond_what_is_owed_are_rejected() {
+        // Italy has lost Naples, so has to disband one of its three units.
+        let mut map = Map::standard();
+        let naples = map.province_by_name("Nap").unwrap();
+        map.province_mut(naples).unwrap().set_owner(NEUTRAL);
+        let mut game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+        let orders: Vec<BuildOrder> = ["Disband F Nap", "Disband A Ven"]
+            .iter()
+            .map(|order| BuildOrder::parse(order, game.map()).unwrap())
+            .collect();
+
+        let result = game.resolve_builds(5, &orders);
+        assert_eq!(result.disbanded, vec![naples]);
+        assert_eq!(
+            result.rejected,
+            vec![(orders[1], OrderError::NoDisbandsLeft(id(&game, "Ven")))]
+        );
+        assert_eq!(game.build_allowance(5), 0);
+    }
+
+    #[test]
+    fn disband_must_name_the_type_of_unit_there() {
+        // Germany has lost Munich, so has to disband one unit.
+        let mut map = Map::standard();
+        let munich = map.province_by_name("Mun").unwrap();
+        map.province_mut(munich).unwrap().set_owner(NEUTRAL);
+        let mut game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+        let fleet = BuildOrder::parse("Disband F Mun", game.map()).unwrap();
+        let army = BuildOrder::parse("Disband A Mun", game.map()).unwrap();
+
+        let result = game.resolve_builds(4, &[fleet, army]);
+        assert_eq!(
+            result.rejected,
+            vec![(fleet, OrderError::NoUnitToDisband(munich))]
+        );
+        assert_eq!(result.disbanded, vec![munich]);
+    }
+
+    #[test]
+    fn fleet_cannot_be_built_inland() {
+        let mut map = Map::standard();
+        let paris = map.province_by_name("Par").unwrap();
+        map.province_mut(paris).unwrap().set_occupant(None);
+        let mut game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+
+        let fleet = BuildOrder::parse("Build F Par", game.map()).unwrap();
+        let result = game.resolve_builds(3, &[fleet]);
+        assert_eq!(
+            result.rejected,
+            vec![(fleet, OrderError::CannotHoldUnit(paris))]
+        );
+
+        let army = BuildOrder::parse("Build A Par", game.map()).unwrap();
+        assert_eq!(
+            game.resolve_builds(3, &[army]).built,
+            vec![(paris, UnitType::Army)]
+        );
+    }
+
+    #[test]
+    fn dislodged_units_lists_every_dislodged_unit() {
+        let mut game = standard_game();
+        let (bur, pic) = (id(&game, "Bur"), id(&game, "Pic"));
+        game.place_units(vec![
+            (bur, 4, UnitType::Army),
+            (pic, 2, UnitType::Army),
+            (id(&game, "Bel"), 3, UnitType::Army),
+        ])
+        .unwrap();
+        assert!(game.map().dislodged_units().is_empty());
+
+        move_units(
+            &mut game,
+            &[
+                "A Par - Bur",
+                "A Mar S A Par - Bur",
+                "F Bre - Pic",
+                "A Bel S F Bre - Pic",
+            ],
+        );
+        let mut dislodged = game.map().dislodged_units();
+        dislodged.sort_by_key(|(province, _)| *province);
+        let (german, english) = (Unit::new(4, UnitType::Army), Unit::new(2, UnitType::Army));
+        let mut expected = vec![(bur, &german), (pic, &english)];
+        expected.sort_by_key(|(province, _)| *province);
+        assert_eq!(dislodged, expected);
+    }
+
+    #[test]
+    fn retreat_phase_is_skipped_when_nothing_was_dislodged() {
+        let mut game = standard_game();
+        move_units(&mut game, &["A Par - Bur"]);
+        assert_eq!((game.phase(), game.year()), (Phase::FallMovement, 1901));
+    }
+
+    #[test]
+    fn builds_are_skipped_when_every_player_has_as_many_units_as_centers() {
+        let mut game = standard_game();
+        move_units(&mut game, &[]);
+        move_units(&mut game, &[]);
+        assert_eq!((game.phase(), game.year()), (Phase::SpringMovement, 1902));
+
+        // Taking a neutral center gives France a build, so winter isn't skipped.
+        move_units(&mut game, &["A Mar - Spa"]);
+        move_units(&mut game, &[]);
+        assert_eq!((game.phase(), game.year()), (Phase::WinterBuild, 1902));
+        assert_eq!(game.build_allowance(3), 1);
+    }
+
+    #[test]
+    fn phase_on_an_invalid_map_is_not_adjudicated() {
+        let mut map = Map::standard();
+        let (par, bur) = (
+            map.province_by_name("Par").unwrap(),
+            map.province_by_name("Bur").unwrap(),
+        );
+        map.province_mut(par).unwrap().set_coast_of(bur);
+        let mut game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+
+        assert_eq!(
+            game.adjudicate(PhaseOrders::Movement(Vec::new()))
+                .unwrap_err(),
+            AdjudicationError::InvalidMap(MapError::InvalidCoast(par))
+        );
+        assert_eq!(game.phase(), Phase::SpringMovement);
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn fall_capture_is_reported_with_the_movement_when_there_are_no_retreats() {
+        let mut game = standard_game();
+        let spain = id(&game, "Spa");
+        let spring = move_units(&mut game, &["A Mar - Spa"]);
+        assert!(spring.sc_transfers.is_empty());
+        assert!(game.map().province(spain).unwrap().is_neutral());
+
+        let fall = move_units(&mut game, &[]);
+        assert_eq!(fall.sc_transfers, vec![(spain, NEUTRAL, 3)]);
+        assert_eq!(game.phase(), Phase::WinterBuild);
+    }
+
+    #[test]
+    fn supply_centers_change_hands_after_the_fall_retreats() {
+        let mut game = standard_game();
+        let (spain, picardy, belgium) = (id(&game, "Spa"), id(&game, "Pic"), id(&game, "Bel"));
+        game.place_units(vec![(picardy, 2, UnitType::Army)])
+            .unwrap();
+        move_units(&mut game, &[]);
+
+        let fall = move_units(
+            &mut game,
+            &["A Mar - Spa", "F Bre - Pic", "A Par S F Bre - Pic"],
+        );
+        assert!(fall.sc_transfers.is_empty());
+        assert_eq!(game.phase(), Phase::FallRetreat);
+        assert!(game.map().province(spain).unwrap().is_neutral());
+
+        // England's army retreats into Belgium, which it takes along with France taking Spain.
+        let order = RetreatOrder::parse("A Pic - Bel", game.map()).unwrap();
+        let outcome = match game.adjudicate(PhaseOrders::Retreat(vec![order])) {
+            Ok(PhaseOutcome::Retreat(outcome)) => outcome,
+            other => panic!("expected a retreat outcome, got {other:?}"),
+        };
+        let mut transfers = outcome.sc_transfers;
+        transfers.sort();
+        let mut expected = vec![(spain, NEUTRAL, 3), (belgium, NEUTRAL, 2)];
+        expected.sort();
+        assert_eq!(transfers, expected);
+        assert_eq!(game.map().province(belgium).unwrap().owned_by(), 2);
+        assert_eq!(game.phase(), Phase::WinterBuild);
+    }
+
+    // A standard game with a unit put straight onto the map, without any of the checks `place_units` makes.
+    fn game_with_unit(name: &str, owner: PlayerID, unit_type: UnitType) -> GameState {
+        let mut map = Map::standard();
+        let province = map.province_by_name(name).unwrap();
+        map.province_mut(province)
+            .unwrap()
+            .set_occupant(Some(Unit::new(owner, unit_type)));
+        GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap()
+    }
+
+    #[test]
+    fn standard_opening_is_a_valid_setup() {
+        assert_eq!(standard_game().validate_setup(), Ok(()));
+    }
+
+    #[test]
+    fn setup_rejects_a_fleet_inland() {
+        let game = game_with_unit("Bur", 3, UnitType::Fleet);
+        assert_eq!(
+            game.validate_setup(),
+            Err(vec![SetupError::CannotHoldUnit(id(&game, "Bur"))])
+        );
+    }
+
+    #[test]
+    fn setup_rejects_a_unit_in_a_province_and_on_its_coast() {
+        // Russia's fleet is already on St Petersburg's south coast.
+        let game = game_with_unit("Stp", 6, UnitType::Army);
+        assert_eq!(
+            game.validate_setup(),
+            Err(vec![SetupError::DoubleOccupancy(id(&game, "Stp"))])
+        );
+    }
+
+    #[test]
+    fn setup_rejects_a_unit_owned_by_an_unknown_player() {
+        let game = game_with_unit("Bur", 9, UnitType::Army);
+        assert_eq!(
+            game.validate_setup(),
+            Err(vec![SetupError::UnknownOwner(id(&game, "Bur"))])
+        );
+    }
+
+    #[test]
+    fn orders_of_the_wrong_kind_for_the_phase_are_rejected() {
+        let mut game = standard_game();
+        assert_eq!(
+            game.adjudicate(PhaseOrders::Retreat(Vec::new()))
+                .unwrap_err(),
+            AdjudicationError::WrongPhase(Phase::SpringMovement)
+        );
+        assert_eq!(
+            game.adjudicate(PhaseOrders::Build(Vec::new())).unwrap_err(),
+            AdjudicationError::WrongPhase(Phase::SpringMovement)
+        );
+        assert!(game.history().is_empty());
+
+        assert!(matches!(
+            game.adjudicate(PhaseOrders::Movement(Vec::new())),
+            Ok(PhaseOutcome::Movement(_))
+        ));
+    }
+
+    #[test]
+    fn rewind_undoes_one_phase_at_a_time() {
+        let mut game = game_with_a_dislodged_army();
+        let bur = id(&game, "Bur");
+        retreat(&mut game, "A Bur - Ruh");
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(game.history()[1].phase, Phase::SpringRetreat);
+
+        // Undoing the retreat puts the dislodged army back, still waiting to retreat.
+        game.rewind().unwrap();
+        assert_eq!(game.phase(), Phase::SpringRetreat);
+        assert_eq!(game.history().len(), 1);
+        assert!(game.map().occupant_owner(id(&game, "Ruh")).is_none());
+        assert_eq!(game.map().dislodged_units().len(), 1);
+
+        // Undoing the movement puts France back in Paris and Germany back in Burgundy.
+        game.rewind().unwrap();
+        assert_eq!(game.phase(), Phase::SpringMovement);
+        assert_eq!(game.map().occupant_owner(bur), Some(4));
+        assert_eq!(game.map().occupant_owner(id(&game, "Par")), Some(3));
+        assert!(game.map().dislodged_units().is_empty());
+        assert_eq!(game.rewind(), Err(RewindError::NoHistory));
+    }
+
+    #[test]
+    fn rewind_gives_back_captured_centers_and_drops_submitted_orders() {
+        let mut game = standard_game();
+        let bel = id(&game, "Bel");
+        let owner_of_bel = |game: &GameState| game.map().province(bel).unwrap().owned_by();
+        move_units(&mut game, &["A Par - Bur"]);
+        move_units(&mut game, &["A Bur - Bel"]);
+        assert_eq!(game.phase(), Phase::WinterBuild);
+        assert_eq!(owner_of_bel(&game), 3);
+
+        game.rewind().unwrap();
+        assert_eq!((game.phase(), game.year()), (Phase::FallMovement, 1901));
+        assert_eq!(owner_of_bel(&game), NEUTRAL);
+        assert_eq!(game.map().occupant_owner(id(&game, "Bur")), Some(3));
+
+        game.submit(OrderSet::new(3, orders(&game, &["A Bur - Bel"])))
+            .unwrap();
+        game.rewind().unwrap();
+        assert_eq!(game.phase(), Phase::SpringMovement);
+        assert!(game.submitted_orders().is_empty());
+        assert!(game.history().is_empty());
+    }
+
+    // A standard game where France has taken all of Italy's centers, and Italy has lost every unit apart from those in
+    // `kept`.
+    fn game_with_italy_reduced_to(kept: &[&str]) -> GameState {
+        let mut map = Map::standard();
+        for name in ["Ven", "Rom", "Nap"] {
+            let province = map.province_by_name(name).unwrap();
+            let province = map.province_mut(province).unwrap();
+            province.set_owner(3);
+            if !kept.contains(&name) {
+                province.set_occupant(None);
+            }
+        }
+        GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap()
+    }
+
+    #[test]
+    fn player_without_centers_or_units_is_eliminated_and_cannot_order() {
+        let mut game = game_with_italy_reduced_to(&[]);
+        assert_eq!(game.eliminated_players(), vec![5]);
+        assert_eq!(
+            game.submit(OrderSet::new(5, Vec::new())),
+            Err(vec![OrderError::PlayerEliminated(5)])
+        );
+        assert_eq!(
+            game.submit(OrderSet::new(3, orders(&game, &["A Par H"]))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn player_with_a_unit_left_is_not_eliminated() {
+        let mut game = game_with_italy_reduced_to(&["Ven"]);
+        assert!(game.eliminated_players().is_empty());
+        assert_eq!(
+            game.submit(OrderSet::new(5, orders(&game, &["A Ven H"]))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn summary_lists_every_order_with_its_outcome() {
+        let mut game = french_game();
+        game.place_units(vec![
+            (id(&game, "Bur"), 4, UnitType::Army),
+            (id(&game, "Lon"), 2, UnitType::Army),
+            (id(&game, "NTH"), 2, UnitType::Fleet),
+        ])
+        .unwrap();
+        let adjudication = move_units(
+            &mut game,
+            &[
+                "A Par - Bur",
+                "A Mar S A Par - Bur",
+                "F Bre - Pic",
+                "A Lon - Nwy",
+                "F NTH C A Lon - Nwy",
+            ],
+        );
+
+        assert_eq!(
+            adjudication.summary(game.map()),
+            "A Par - Bur (success)\n\
+             A Mar S A Par - Bur (success)\n\
+             F Bre - Pic (success)\n\
+             A Lon - Nwy (success)\n\
+             F NTH C A Lon - Nwy (success)\n\
+             A Bur H (dislodged)"
+        );
+    }
+
+    // Adjudicates a spring turn with France's and England's orders submitted in the given order, returning the summary
+    // of the turn with the outcomes sorted by province.
+    fn sorted_summary(england_first: bool) -> String {
+        let mut game = standard_game();
+        let mut sets = vec![
+            OrderSet::new(3, orders(&game, &["A Par - Bur", "F Bre - MAO"])),
+            OrderSet::new(2, orders(&game, &["F Lon - ENG", "A Lvp - Yor"])),
+        ];
+        if england_first {
+            sets.reverse();
+        }
+        for set in sets {
+            game.submit(set).unwrap();
+        }
+
+        let mut adjudication = match game.adjudicate(PhaseOrders::Movement(game.submitted_orders()))
+        {
+            Ok(PhaseOutcome::Movement(adjudication)) => adjudication,
+            other => panic!("expected a movement outcome, got {other:?}"),
+        };
+        order::sort_orders_by_province(&mut adjudication.outcomes);
+        adjudication.summary(game.map())
+    }
+
+    #[test]
+    fn sorted_renders_of_the_same_turn_are_identical() {
+        assert_eq!(sorted_summary(false), sorted_summary(true));
+
+        let map = Map::standard();
+        let names: Vec<&str> = map
+            .provinces_sorted()
+            .iter()
+            .map(|province| province.province_name())
+            .collect();
+        assert!(
+            names
+                .windows(2)
+                .all(|pair| pair[0].to_lowercase() <= pair[1].to_lowercase())
+        );
+        assert_eq!(names.len(), map.province_count());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn snapshot_round_trips_through_bytes() {
+        let mut game = standard_game();
+        move_units(&mut game, &["A Par - Bur"]);
+        let snapshot = game.to_snapshot();
+
+        let bytes = snapshot.to_bytes();
+        assert_eq!(bytes[0], SNAPSHOT_FORMAT_VERSION);
+        assert_eq!(GameSnapshot::from_bytes(&bytes), Ok(snapshot));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bytes_of_another_format_version_are_rejected() {
+        let mut bytes = standard_game().to_snapshot().to_bytes();
+        bytes[0] = SNAPSHOT_FORMAT_VERSION + 1;
+        assert_eq!(
+            GameSnapshot::from_bytes(&bytes),
+            Err(SnapshotError::UnsupportedVersion(
+                SNAPSHOT_FORMAT_VERSION + 1
+            ))
+        );
+        assert_eq!(
+            GameSnapshot::from_bytes(&[]),
+            Err(SnapshotError::InvalidBytes)
+        );
+        assert_eq!(
+            GameSnapshot::from_bytes(&[SNAPSHOT_FORMAT_VERSION, 0xff]),
+            Err(SnapshotError::InvalidBytes)
+        );
+    }
+
+    #[test]
+    fn standard_opening_is_placed_on_an_empty_board() {
+        let mut map = Map::standard();
+        let occupied: Vec<ProvinceID> = map.occupied().map(|(province, _)| province).collect();
+        for province in occupied {
+            map.province_mut(province).unwrap().set_occupant(None);
+        }
+        let mut game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+
+        let opening = [
+            (1, "A Vie"),
+            (1, "A Bud"),
+            (1, "F Tri"),
+            (2, "F Lon"),
+            (2, "F Edi"),
+            (2, "A Lvp"),
+            (3, "F Bre"),
+            (3, "A Par"),
+            (3, "A Mar"),
+            (4, "F Kie"),
+            (4, "A Ber"),
+            (4, "A Mun"),
+            (5, "F Nap"),
+            (5, "A Rom"),
+            (5, "A Ven"),
+            (6, "F Stp/sc"),
+            (6, "A Mos"),
+            (6, "A War"),
+            (6, "F Sev"),
+            (7, "F Ank"),
+            (7, "A Con"),
+            (7, "A Smy"),
+        ];
+        let placements = opening
+            .iter()
+            .map(|(owner, unit)| {
+                let (unit_type, province) = unit.split_once(' ').unwrap();
+                let unit_type = if unit_type == "A" {
+                    UnitType::Army
+                } else {
+                    UnitType::Fleet
+                };
+                (id(&game, province), *owner, unit_type)
+            })
+            .collect();
+        game.place_units(placements).unwrap();
+
+        for player in 1..=7 {
+            let expected = if player == 6 { 4 } else { 3 };
+            assert_eq!(game.unit_count(player), expected, "player {player}");
+        }
+        let placed: Vec<UnitView> = game.units();
+        assert_eq!(placed, standard_game().units());
+
+        // The board is now full where the opening units are.
+        assert_eq!(
+            game.place_units(vec![(id(&game, "Par"), 3, UnitType::Army)]),
+            Err(vec![SetupError::DoubleOccupancy(id(&game, "Par"))])
+        );
+    }
+
+    #[test]
+    fn player_with_more_centers_than_units_can_build_in_open_home_centers() {
+        // Russia has lost its units in St Petersburg and Moscow, but still owns both.
+        let game = russian_builds_game();
+        assert_eq!(game.build_allowance(6), 2);
+
+        let mut open = game.open_build_centers(6);
+        open.sort();
+        let mut expected = vec![id(&game, "Stp"), id(&game, "Mos")];
+        expected.sort();
+        assert_eq!(open, expected);
+        assert!(game.open_build_centers(3).is_empty());
+    }
+
+    #[test]
+    fn player_with_fewer_centers_than_units_must_disband() {
+        // Germany has lost Munich to France, but still has all three of its units.
+        let mut map = Map::standard();
+        let munich = map.province_by_name("Mun").unwrap();
+        map.province_mut(munich).unwrap().set_owner(3);
+        let game =
+            GameState::new(map, Player::standard_powers(), VariantConfig::standard()).unwrap();
+
+        assert_eq!(game.build_allowance(4), -1);
+        assert_eq!(game.build_allowance(3), 1);
+        // France still can't build: Munich isn't one of its home centers, and its home centers are all occupied.
+        assert!(game.open_build_centers(3).is_empty());
+    }
+
+    #[test]
+    fn units_retreating_to_the_same_province_are_all_disbanded() {
+        let mut game = standard_game();
+        let (bur, pic, hol) = (id(&game, "Bur"), id(&game, "Pic"), id(&game, "Hol"));
+        game.place_units(vec![
+            (bur, 4, UnitType::Army),
+            (pic, 4, UnitType::Army),
+            (hol, 4, UnitType::Army),
+            (id(&game, "Bel"), 3, UnitType::Army),
+            (id(&game, "NTH"), 2, UnitType::Fleet),
+            (id(&game, "HEL"), 2, UnitType::Fleet),
+        ])
+        .unwrap();
+        move_units(
+            &mut game,
+            &[
+                "A Par - Bur",
+                "A Mar S A Par - Bur",
+                "F Bre - Pic",
+                "A Bel S F Bre - Pic",
+                "F NTH - Hol",
+                "F HEL S F NTH - Hol",
+            ],
+        );
+        assert_eq!(game.map().dislodged_units().len(), 3);
+
+        let retreats = ["A Bur - Ruh", "A Hol - Ruh", "A Pic - Par"]
+            .iter()
+            .map(|order| RetreatOrder::parse(order, game.map()).unwrap())
+            .collect();
+        let outcome = match game.adjudicate(PhaseOrders::Retreat(retreats)) {
+            Ok(PhaseOutcome::Retreat(outcome)) => outcome,
+            other => panic!("expected a retreat outcome, got {other:?}"),
+        };
+
+        let mut disbanded = outcome.disbanded;
+        disbanded.sort();
+        let mut expected = vec![bur, hol];
+        expected.sort();
+        assert_eq!(disbanded, expected);
+        assert_eq!(game.map().occupant_owner(id(&game, "Ruh")), None);
+        assert_eq!(game.map().occupant_owner(id(&game, "Par")), Some(4));
+        assert_eq!(game.unit_count(4), 4);
+    }
+}
@@ -1,5 +1,13 @@
+pub mod build;
+#[cfg(test)]
+mod datc;
+pub mod error;
+pub mod game_state;
 pub mod map;
 pub mod order;
+pub mod parse;
 pub mod player;
 pub mod province;
+pub mod retreat;
 pub mod unit;
+pub mod variant;
@@ -1,10 +1,19 @@
-use petgraph::{Graph, Undirected, graph::UnGraph};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use petgraph::{
+    Graph, Undirected,
+    graph::{NodeIndex, UnGraph},
+};
 
 use crate::{
-    province::{Province, ProvinceID},
-    unit::UnitType,
+    error::{ConnectionError, MapError},
+    parse::{Table, parse_province_type, parse_unit_type, split_coast},
+    player::{NEUTRAL, Player, PlayerID},
+    province::{Province, ProvinceID, ProvinceType},
+    unit::{Unit, UnitType},
 };
 
+#[derive(Clone)]
 pub struct Connection {
     province_1_id: ProvinceID,
     province_2_id: ProvinceID,
@@ -16,10 +25,9 @@ impl Connection {
         province_1_id: ProvinceID,
         province_2_id: ProvinceID,
         allowed_unit_types: Vec<UnitType>,
-    ) -> Self {
-        // TODO: Error handling?
+    ) -> Result<Self, ConnectionError> {
         if province_1_id == province_2_id {
-            panic!("Not allowed!")
+            return Err(ConnectionError::SameProvince(province_1_id));
         }
 
         let (id1, id2) = {
@@ -29,28 +37,1802 @@ impl Connection {
                 (province_2_id, province_1_id)
             }
         };
-        Self {
+        Ok(Self {
             province_1_id: id1,
             province_2_id: id2,
             allowed_unit_types,
-        }
+        })
+    }
+
+    // A connection only fleets can use, i.e. between two seas or along a coast.
+    pub fn fleet(
+        province_1_id: ProvinceID,
+        province_2_id: ProvinceID,
+    ) -> Result<Self, ConnectionError> {
+        Self::new(province_1_id, province_2_id, vec![UnitType::Fleet])
+    }
+
+    // A connection only armies can use, i.e. between two inland provinces.
+    pub fn army(
+        province_1_id: ProvinceID,
+        province_2_id: ProvinceID,
+    ) -> Result<Self, ConnectionError> {
+        Self::new(province_1_id, province_2_id, vec![UnitType::Army])
+    }
+
+    // A connection both armies and fleets can use, i.e. between two coastal provinces that share a coast.
+    pub fn both(
+        province_1_id: ProvinceID,
+        province_2_id: ProvinceID,
+    ) -> Result<Self, ConnectionError> {
+        Self::new(
+            province_1_id,
+            province_2_id,
+            vec![UnitType::Army, UnitType::Fleet],
+        )
     }
 
     pub fn allowed(&self, unit_type: &UnitType) -> bool {
         self.allowed_unit_types.contains(unit_type)
     }
+
+    pub fn allowed_unit_types(&self) -> &[UnitType] {
+        &self.allowed_unit_types
+    }
+
+    pub fn province_1_id(&self) -> ProvinceID {
+        self.province_1_id
+    }
+
+    pub fn province_2_id(&self) -> ProvinceID {
+        self.province_2_id
+    }
+
+    // Given one end of this connection, returns the other end. None if `province_id` isn't part of this connection.
+    pub fn other_end(&self, province_id: ProvinceID) -> Option<ProvinceID> {
+        if province_id == self.province_1_id {
+            Some(self.province_2_id)
+        } else if province_id == self.province_2_id {
+            Some(self.province_1_id)
+        } else {
+            None
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct Map {
+    // All provinces on the map. A province's ID is always its index in this list.
     provinces: Vec<Province>,
-    adjacency_graph: UnGraph<ProvinceID, Vec<UnitType>>,
+
+    // Node `i` of the graph is always the province with ID `i`.
+    adjacency_graph: UnGraph<ProvinceID, Connection>,
+
+    // Every province ID, keyed by its name in lowercase.
+    province_ids_by_name: HashMap<String, ProvinceID>,
 }
 
 impl Map {
     pub fn new() -> Self {
         Self {
             provinces: Vec::<Province>::new(),
-            adjacency_graph: Graph::<ProvinceID, Vec<UnitType>, Undirected>::new_undirected(),
+            adjacency_graph: Graph::<ProvinceID, Connection, Undirected>::new_undirected(),
+            province_ids_by_name: HashMap::new(),
+        }
+    }
+
+    // The standard map, read from `maps/standard_map.csv` and `maps/standard_map_connections.csv`. Every unit is in its
+    // starting position, and each home center is owned by its power (see `Player::standard_powers`).
+    pub fn standard() -> Self {
+        Self::from_tables(
+            include_str!("../../maps/standard_map.csv"),
+            include_str!("../../maps/standard_map_connections.csv"),
+            &Player::standard_powers(),
+        )
+        .expect("The standard map's tables should be valid")
+    }
+
+    // Builds a map from a table of provinces, one row per province, listed in order of their IDs. Fails if a row's ID isn't
+    // the next one in order, or if a coast is listed before its parent.
+    pub fn from_province_table(rows: impl Iterator<Item = ProvinceRow>) -> Result<Self, MapError> {
+        let mut map = Self::new();
+
+        for row in rows {
+            if row.province_id as usize != map.province_count() {
+                return Err(MapError::ProvinceOutOfOrder(row.province_id));
+            }
+
+            let mut province = Province::new(row.province_id, row.province_name, row.province_type);
+            province.set_owner(row.owned_by);
+            province.set_sc_value(row.sc_value);
+            for core in row.core_of {
+                province.add_core(core);
+            }
+            if let Some(parent) = row.is_coast_of {
+                if map.province(parent).is_none() {
+                    return Err(MapError::UnknownProvince(parent));
+                }
+                province.set_coast_of(parent);
+            }
+            province.set_occupant(row.occupied_by);
+            map.add_province(province);
+        }
+
+        Ok(map)
+    }
+
+    // Builds a map from the text of a province table and a connection table (see `ProvinceRow::parse_table` and
+    // `ConnectionRow::parse_table`), and checks that it is valid.
+    pub fn from_tables(
+        province_table: &str,
+        connection_table: &str,
+        players: &[Player],
+    ) -> Result<Self, MapError> {
+        let mut map = Self::from_province_table(
+            ProvinceRow::parse_table(province_table, players)?.into_iter(),
+        )?;
+        let connections = ConnectionRow::parse_table(connection_table, &map)?;
+        map.add_connection_table(connections.into_iter())?;
+        map.validate()?;
+        Ok(map)
+    }
+
+    // Adds every connection in a table of connections, one row per connection. Stops at the first row that isn't a valid
+    // connection between two provinces on the map; the rows before it are kept.
+    pub fn add_connection_table(
+        &mut self,
+        rows: impl Iterator<Item = ConnectionRow>,
+    ) -> Result<(), MapError> {
+        for row in rows {
+            if let Some(missing) = [row.province_1_id, row.province_2_id]
+                .into_iter()
+                .find(|id| self.province(*id).is_none())
+            {
+                return Err(MapError::UnknownProvince(missing));
+            }
+            self.add_connection(Connection::new(
+                row.province_1_id,
+                row.province_2_id,
+                row.allowed_unit_types,
+            )?);
+        }
+        Ok(())
+    }
+
+    // Checks that every coast and its parent agree with each other: a coast's parent must be on the map and list it as one
+    // of its coasts, and everything a province lists as its coasts must be a coast of that province. A coast is never a
+    // supply center itself; if its parent is one, the coast may have an `sc_value` of 0.
+    pub fn validate(&self) -> Result<(), MapError> {
+        for province in &self.provinces {
+            if let Some(parent_id) = province.is_coast_of() {
+                let parent = self
+                    .province(parent_id)
+                    .ok_or(MapError::UnknownProvince(parent_id))?;
+                if province.province_type() != ProvinceType::Coast
+                    || !parent.has_coasts().contains(&province.province_id())
+                {
+                    return Err(MapError::InvalidCoast(province.province_id()));
+                }
+
+                // A coast of a supply center is given an `sc_value` of 0 so that it can be built on, but it is the parent
+                // that counts.
+                let coast_counts = province.sc_value().is_some_and(|value| value > 0);
+                let coast_without_center =
+                    province.sc_value() == Some(0) && !parent.is_supply_center();
+                if coast_counts || coast_without_center {
+                    return Err(MapError::CoastSupplyCenter(province.province_id()));
+                }
+            }
+            for coast_id in province.has_coasts() {
+                let coast = self
+                    .province(*coast_id)
+                    .ok_or(MapError::UnknownProvince(*coast_id))?;
+                if coast.is_coast_of() != Some(province.province_id()) {
+                    return Err(MapError::InvalidCoast(*coast_id));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Adds a province to the map. Provinces must be added in order of their IDs, starting at 0.
+    // If the province is the coast of another province, the parent's `has_coasts` is updated to match.
+    pub fn add_province(&mut self, province: Province) {
+        // TODO: Error handling?
+        if province.province_id() as usize != self.provinces.len() {
+            panic!("Provinces must be added in order of their IDs!")
+        }
+
+        if let Some(parent_id) = province.is_coast_of() {
+            self.provinces
+                .get_mut(parent_id as usize)
+                .expect("The parent of a coast must be added before the coast itself!")
+                .add_coast(province.province_id());
+        }
+
+        self.adjacency_graph.add_node(province.province_id());
+        self.province_ids_by_name.insert(
+            province.province_name().to_lowercase(),
+            province.province_id(),
+        );
+        self.provinces.push(province);
+    }
+
+    pub fn add_connection(&mut self, connection: Connection) {
+        let node_1 = NodeIndex::new(connection.province_1_id() as usize);
+        let node_2 = NodeIndex::new(connection.province_2_id() as usize);
+        self.adjacency_graph.add_edge(node_1, node_2, connection);
+    }
+
+    // Removes the connection between two provinces. Returns whether there was one to remove. An `Adjacency` built before
+    // this (see `build_adjacency`) isn't updated, so build a new one after editing the map.
+    pub fn remove_connection(&mut self, province_1: ProvinceID, province_2: ProvinceID) -> bool {
+        let node_1 = NodeIndex::new(province_1 as usize);
+        let node_2 = NodeIndex::new(province_2 as usize);
+
+        let mut removed = false;
+        while let Some(edge) = self.adjacency_graph.find_edge(node_1, node_2) {
+            self.adjacency_graph.remove_edge(edge);
+            removed = true;
+        }
+        removed
+    }
+
+    // Changes which unit types may use the connection between two provinces. Returns false, and changes nothing, if the two
+    // provinces aren't connected.
+    pub fn set_connection_unit_types(
+        &mut self,
+        province_1: ProvinceID,
+        province_2: ProvinceID,
+        unit_types: Vec<UnitType>,
+    ) -> bool {
+        let node_1 = NodeIndex::new(province_1 as usize);
+        let node_2 = NodeIndex::new(province_2 as usize);
+
+        match self
+            .adjacency_graph
+            .find_edge(node_1, node_2)
+            .and_then(|edge| self.adjacency_graph.edge_weight_mut(edge))
+        {
+            Some(connection) => {
+                connection.allowed_unit_types = unit_types;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Whether either an army or a fleet can move directly between two provinces (see `neighbors`).
+    pub fn are_adjacent(&self, province_1: ProvinceID, province_2: ProvinceID) -> bool {
+        [UnitType::Army, UnitType::Fleet]
+            .iter()
+            .any(|unit_type| self.neighbors(province_1, unit_type).contains(&province_2))
+    }
+
+    pub fn province(&self, province_id: ProvinceID) -> Option<&Province> {
+        self.provinces.get(province_id as usize)
+    }
+
+    // Finds a province by its name, ignoring case. A coast can also be named after its parent, followed by the coast after
+    // a slash, in brackets, or after a space (i.e. "StP/nc", "StP(nc)" or "StP NC" for a coast named "Stp NC" or "n.c." of
+    // "StP").
+    pub fn province_by_name(&self, name: &str) -> Option<ProvinceID> {
+        if let Some(id) = self.province_ids_by_name.get(&name.to_lowercase()) {
+            return Some(*id);
+        }
+
+        // Spaces and dots are ignored when matching coasts, so that "nc" matches both "NC" and "n.c.".
+        let simplify = |name: &str| {
+            name.chars()
+                .filter(|c| !matches!(c, ' ' | '.' | '/'))
+                .collect::<String>()
+                .to_lowercase()
+        };
+
+        let (parent_name, coast_name) = split_coast(name)?;
+        let parent = self.province_by_name(parent_name)?;
+        let coast_name = simplify(coast_name);
+        let full_name = simplify(parent_name) + &coast_name;
+
+        self.province(parent)?
+            .has_coasts()
+            .iter()
+            .copied()
+            .find(|coast| {
+                self.province(*coast).is_some_and(|coast| {
+                    let simplified = simplify(coast.province_name());
+                    simplified == coast_name || simplified == full_name
+                })
+            })
+    }
+
+    // Whether the two provinces are the same, or one is a coast of the other (or both are coasts of the same province).
+    pub fn is_same_province(&self, province_1: ProvinceID, province_2: ProvinceID) -> bool {
+        self.parent_of_coast(province_1).unwrap_or(province_1)
+            == self.parent_of_coast(province_2).unwrap_or(province_2)
+    }
+
+    pub fn province_mut(&mut self, province_id: ProvinceID) -> Option<&mut Province> {
+        self.provinces.get_mut(province_id as usize)
+    }
+
+    pub fn province_count(&self) -> usize {
+        self.provinces.len()
+    }
+
+    // Every province on the map, in order of their IDs.
+    pub fn provinces(&self) -> impl Iterator<Item = &Province> {
+        self.provinces.iter()
+    }
+
+    // Every province, sorted by name (ignoring case) rather than by ID as `provinces` is.
+    pub fn provinces_sorted(&self) -> Vec<&Province> {
+        let mut provinces: Vec<&Province> = self.provinces.iter().collect();
+        provinces.sort_by_key(|province| province.province_name().to_lowercase());
+        provinces
+    }
+
+    // Every unit on the map, along with the province (or coast) it is in.
+    pub fn occupied(&self) -> impl Iterator<Item = (ProvinceID, &Unit)> {
+        self.provinces
+            .iter()
+            .filter_map(|province| Some((province.province_id(), province.occupied_by()?)))
+    }
+
+    pub fn connections(&self) -> impl Iterator<Item = &Connection> {
+        self.adjacency_graph.edge_weights()
+    }
+
+    // Every connection with `province_id` at one end, whatever unit types may use it. A coast's connections aren't included
+    // in its parent's, as they are separate provinces on the map.
+    pub fn connections_of(&self, province_id: ProvinceID) -> Vec<&Connection> {
+        if self.province(province_id).is_none() {
+            return Vec::new();
+        }
+
+        self.adjacency_graph
+            .edges(NodeIndex::new(province_id as usize))
+            .map(|edge| edge.weight())
+            .collect()
+    }
+
+    // Every unit waiting to retreat, along with the province (or coast) it was dislodged from.
+    pub fn dislodged_units(&self) -> Vec<(ProvinceID, &Unit)> {
+        self.provinces
+            .iter()
+            .filter_map(|province| Some((province.province_id(), province.disloged_unit()?)))
+            .collect()
+    }
+
+    // Every supply center on the map along with who owns it, in order of their IDs. Coasts of supply centers aren't supply
+    // centers themselves, so each supply center is only listed once.
+    pub fn supply_centers(&self) -> Vec<(ProvinceID, PlayerID)> {
+        self.provinces
+            .iter()
+            .filter(|province| province.is_supply_center())
+            .map(|province| (province.province_id(), province.owned_by()))
+            .collect()
+    }
+
+    // Counts of the provinces and connections on the map, as a quick check of a new variant (i.e. a map with no water at
+    // all has almost certainly been written wrong).
+    pub fn stats(&self) -> MapStats {
+        let count = |province_type: ProvinceType| {
+            self.provinces
+                .iter()
+                .filter(|province| province.province_type() == province_type)
+                .count()
+        };
+
+        MapStats {
+            province_count: self.provinces.len(),
+            sc_count: self.supply_centers().len(),
+            land: count(ProvinceType::Land),
+            coast: count(ProvinceType::Coast),
+            water: count(ProvinceType::Water),
+            deepsea: count(ProvinceType::DeepSea),
+            connection_count: self.adjacency_graph.edge_count(),
+        }
+    }
+
+    // The supply centers that `player` may build in, regardless of who currently owns them.
+    pub fn home_centers(&self, player: PlayerID) -> Vec<ProvinceID> {
+        self.provinces
+            .iter()
+            .filter(|province| province.is_supply_center() && province.core_of().contains(&player))
+            .map(|province| province.province_id())
+            .collect()
+    }
+
+    // Every province at sea, including deep sea: the provinces that fleets can convoy through. Coasts aren't included, as
+    // they are part of a land province.
+    pub fn water_provinces(&self) -> Vec<ProvinceID> {
+        self.provinces
+            .iter()
+            .filter(|province| province.province_type().can_convoy_through())
+            .map(|province| province.province_id())
+            .collect()
+    }
+
+    // Every coastal land province, where both armies and fleets can be (see `is_coastal`).
+    pub fn coastal_provinces(&self) -> Vec<ProvinceID> {
+        self.provinces
+            .iter()
+            .map(|province| province.province_id())
+            .filter(|id| self.is_coastal(*id))
+            .collect()
+    }
+
+    // Whether this is a land province that borders the sea: one with a fleet connection, or with split coasts. The coasts of
+    // a province with split coasts (`ProvinceType::Coast`) aren't coastal themselves, as armies can't be on them; their parent
+    // is instead.
+    pub fn is_coastal(&self, province_id: ProvinceID) -> bool {
+        self.province(province_id).is_some_and(|province| {
+            province.province_type() == ProvinceType::Land
+                && (!province.has_coasts().is_empty()
+                    || !self.neighbors(province_id, &UnitType::Fleet).is_empty())
+        })
+    }
+
+    // Whether the province, or any of its coasts, is next to a sea that a fleet could convoy through. An army can only ever
+    // be convoyed out of a province that is.
+    pub fn borders_sea(&self, province_id: ProvinceID) -> bool {
+        self.province_and_coasts(province_id).into_iter().any(|id| {
+            self.neighbors(id, &UnitType::Fleet)
+                .into_iter()
+                .any(|neighbor| {
+                    self.province(neighbor)
+                        .is_some_and(|neighbor| neighbor.province_type().can_convoy_through())
+                })
+        })
+    }
+
+    // Whether a unit of `unit_type` ordered into the province has to say which coast it is going to. Only fleets do, and only
+    // into a province with split coasts (i.e. Spain, but not Portugal).
+    pub fn requires_coast_choice(&self, province_id: ProvinceID, unit_type: &UnitType) -> bool {
+        *unit_type == UnitType::Fleet
+            && self
+                .province(province_id)
+                .is_some_and(|province| !province.has_coasts().is_empty())
+    }
+
+    // All provinces that a unit of the given type can move to directly from `province_id`. A connection is only followed if
+    // it allows the unit type and the unit could be in the provinces at both ends, so a connection that wrongly lets armies
+    // into the sea is never used by one.
+    pub fn neighbors(&self, province_id: ProvinceID, unit_type: &UnitType) -> Vec<ProvinceID> {
+        let can_hold = |province_id: ProvinceID| {
+            self.province(province_id)
+                .is_some_and(|province| province.province_type().can_hold(unit_type))
+        };
+        if !can_hold(province_id) {
+            return Vec::new();
+        }
+
+        self.adjacency_graph
+            .edges(NodeIndex::new(province_id as usize))
+            .filter(|edge| edge.weight().allowed(unit_type))
+            .filter_map(|edge| edge.weight().other_end(province_id))
+            .filter(|neighbor| can_hold(*neighbor))
+            .collect()
+    }
+
+    // The provinces the unit in `province_id` can move to without being convoyed, i.e. for listing the moves it can be
+    // ordered to make. Empty if there is no unit there. See `convoy_reachable` for where it could be convoyed to as well.
+    pub fn direct_neighbors(&self, province_id: ProvinceID) -> Vec<ProvinceID> {
+        self.province(province_id)
+            .and_then(|province| province.occupied_by())
+            .map_or_else(Vec::new, |unit| {
+                self.neighbors(province_id, &unit.get_type())
+            })
+    }
+
+    // The provinces the unit in `province_id` can only reach by being convoyed by the fleets in `convoying_fleets` (see
+    // `convoy_routes`), in order of their IDs. Provinces it could move to directly aren't included, and neither is anywhere
+    // for a unit that can't be convoyed.
+    pub fn convoy_reachable(
+        &self,
+        province_id: ProvinceID,
+        convoying_fleets: &[ProvinceID],
+    ) -> Vec<ProvinceID> {
+        let can_be_convoyed = self
+            .province(province_id)
+            .and_then(|province| province.occupied_by())
+            .is_some_and(|unit| unit.can_be_convoyed());
+        if !can_be_convoyed {
+            return Vec::new();
+        }
+
+        let direct = self.direct_neighbors(province_id);
+        self.provinces
+            .iter()
+            .map(|province| province.province_id())
+            .filter(|to| *to != province_id && !direct.contains(to))
+            .filter(|to| {
+                !self
+                    .convoy_routes(province_id, *to, convoying_fleets)
+                    .is_empty()
+            })
+            .collect()
+    }
+
+    // Precomputes `neighbors` for every province and unit type, for callers that look them up many times on the same map.
+    // The result isn't updated if connections are added afterwards.
+    pub fn build_adjacency(&self) -> Adjacency {
+        let neighbors_for = |unit_type: UnitType| {
+            self.provinces
+                .iter()
+                .map(|province| self.neighbors(province.province_id(), &unit_type))
+                .collect()
+        };
+
+        Adjacency {
+            army_neighbors: neighbors_for(UnitType::Army),
+            fleet_neighbors: neighbors_for(UnitType::Fleet),
+        }
+    }
+
+    // The shortest sequence of moves for a unit of the given type to get from `from` to `to`, ignoring any other units.
+    // The path starts with `from` and ends with `to`, so a unit already in `to` gets a path of just `from`. None if the unit
+    // can't get there at all (i.e. an army trying to reach an island), as convoys aren't considered.
+    pub fn shortest_path(
+        &self,
+        from: ProvinceID,
+        to: ProvinceID,
+        unit_type: &UnitType,
+    ) -> Option<Vec<ProvinceID>> {
+        self.province(from)?;
+        self.province(to)?;
+
+        // Breadth first search, remembering where each province was first reached from.
+        let mut visited = vec![false; self.provinces.len()];
+        let mut reached_from: Vec<Option<ProvinceID>> = vec![None; self.provinces.len()];
+        let mut queue = VecDeque::from([from]);
+        visited[from as usize] = true;
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to];
+                while let Some(previous) = reached_from[*path.last().unwrap() as usize] {
+                    path.push(previous);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for next in self.neighbors(current, unit_type) {
+                if !visited[next as usize] {
+                    visited[next as usize] = true;
+                    reached_from[next as usize] = Some(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    // Whether holding every province in `provinces` cuts the units of `for_player` off from every supply center they don't
+    // own yet, i.e. whether the provinces form a line that the player can't get past. A unit can't move into or through any of
+    // the held provinces or their coasts, other units are ignored, and convoys aren't considered (as with `shortest_path`).
+    // A player with no units, or no supply center left to reach, is always cut off.
+    pub fn is_cut(&self, provinces: &[ProvinceID], for_player: PlayerID) -> bool {
+        let held: HashSet<ProvinceID> = provinces
+            .iter()
+            .flat_map(|province| self.province_and_coasts(*province))
+            .collect();
+        let targets: HashSet<ProvinceID> = self
+            .supply_centers()
+            .into_iter()
+            .filter(|(province, owner)| *owner != for_player && !held.contains(province))
+            .map(|(province, _)| province)
+            .collect();
+
+        let reaches_target = |start: ProvinceID, unit_type: UnitType| {
+            let mut visited = HashSet::from([start]);
+            let mut queue = VecDeque::from([start]);
+
+            while let Some(current) = queue.pop_front() {
+                let parent = self.parent_of_coast(current).unwrap_or(current);
+                if targets.contains(&parent) {
+                    return true;
+                }
+
+                for next in self.neighbors(current, &unit_type) {
+                    if !held.contains(&next) && visited.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            false
+        };
+
+        !self
+            .occupied()
+            .filter(|(_, unit)| unit.owner() == for_player)
+            .any(|(province, unit)| reaches_target(province, unit.get_type()))
+    }
+
+    // For a province with split coasts (i.e. Spain), returns each coast along with the provinces a fleet on that coast can move to.
+    // Returns an empty list for provinces without coasts.
+    pub fn coast_connections(&self, parent: ProvinceID) -> Vec<(ProvinceID, Vec<ProvinceID>)> {
+        match self.province(parent) {
+            Some(province) => province
+                .has_coasts()
+                .iter()
+                .map(|coast| (*coast, self.neighbors(*coast, &UnitType::Fleet)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // The province that `coast` is a coast of (i.e. Spain for Spain North Coast). None if `coast` isn't a coast.
+    pub fn parent_of_coast(&self, coast: ProvinceID) -> Option<ProvinceID> {
+        self.province(coast)?.is_coast_of()
+    }
+
+    // Whether there is a unit in the province or on any of its coasts. Coasts count as part of their parent, so for a coast
+    // this checks the parent and every one of its coasts.
+    // Only one unit may be in a province at a time, i.e. a fleet can't be on Spain North Coast while an army is in Spain.
+    pub fn is_province_occupied_including_coasts(&self, province_id: ProvinceID) -> bool {
+        self.occupant_including_coasts(province_id).is_some()
+    }
+
+    // The player whose unit is in the province or on any of its coasts, if there is one. As with
+    // `is_province_occupied_including_coasts`, a coast is treated as its parent.
+    pub fn occupant_owner(&self, province_id: ProvinceID) -> Option<PlayerID> {
+        self.occupant_including_coasts(province_id)
+            .map(|(_, unit)| unit.owner())
+    }
+
+    // The province itself along with all of its coasts. If given a coast, the parent is used.
+    fn province_and_coasts(&self, province_id: ProvinceID) -> Vec<ProvinceID> {
+        let parent = self.parent_of_coast(province_id).unwrap_or(province_id);
+
+        match self.province(parent) {
+            Some(province) => std::iter::once(parent)
+                .chain(province.has_coasts().iter().copied())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Every way an army in `from` can be convoyed to `to` using only the fleets in `convoying_fleets`. Each route lists the
+    // fleets it uses, in order from `from` to `to`.
+    // Fleets that aren't somewhere they can convoy from (i.e. fleets on a coast) are ignored, while fleets at sea, including
+    // deep sea, can be anywhere along the route. There are no routes at all if an army can't be convoyed out of `from` or
+    // into `to` (i.e. either one is at sea). Routes stop as soon as they reach `to`, and routes using the same fleets in a
+    // different order are only listed once.
+    pub fn convoy_routes(
+        &self,
+        from: ProvinceID,
+        to: ProvinceID,
+        convoying_fleets: &[ProvinceID],
+    ) -> Vec<Vec<ProvinceID>> {
+        let can_convoy_between = self
+            .province(from)
+            .is_some_and(|province| province.province_type().can_convoy_out_of())
+            && self
+                .province(to)
+                .is_some_and(|province| province.province_type().can_convoy_into());
+        if !can_convoy_between {
+            return Vec::new();
+        }
+
+        // Neither end of the convoy can be part of the route, even if it is listed as one of the fleets (such as a fleet in
+        // `to` that was mistakenly ordered to convoy the army into its own province). Both ends are land, so this is only
+        // a safeguard, as no fleet there could convoy anyway.
+        let fleets: Vec<ProvinceID> = convoying_fleets
+            .iter()
+            .copied()
+            .filter(|fleet| {
+                !self.is_same_province(*fleet, from) && !self.is_same_province(*fleet, to)
+            })
+            .filter(|fleet| {
+                self.province(*fleet)
+                    .is_some_and(|province| province.province_type().can_convoy_through())
+            })
+            .collect();
+
+        // A fleet is next to a province if it is next to the province itself, or any of its coasts.
+        let next_to = |fleet: ProvinceID, province: ProvinceID| {
+            self.province_and_coasts(province)
+                .into_iter()
+                .any(|id| self.neighbors(id, &UnitType::Fleet).contains(&fleet))
+        };
+
+        let mut routes = Vec::new();
+        let mut used_fleet_sets = HashSet::new();
+
+        // Depth first search through every chain of fleets, starting with those next to `from`.
+        let mut stack: Vec<Vec<ProvinceID>> = fleets
+            .iter()
+            .filter(|fleet| next_to(**fleet, from))
+            .map(|fleet| vec![*fleet])
+            .collect();
+
+        while let Some(route) = stack.pop() {
+            let last = *route.last().unwrap();
+
+            if next_to(last, to) {
+                let mut fleet_set = route.clone();
+                fleet_set.sort();
+                if used_fleet_sets.insert(fleet_set) {
+                    routes.push(route);
+                }
+                continue;
+            }
+
+            for next in self.neighbors(last, &UnitType::Fleet) {
+                if fleets.contains(&next) && !route.contains(&next) {
+                    let mut extended_route = route.clone();
+                    extended_route.push(next);
+                    stack.push(extended_route);
+                }
+            }
+        }
+
+        routes
+    }
+
+    // The unit in the province or on any of its coasts, along with the exact province (or coast) it is in.
+    pub(crate) fn occupant_including_coasts(
+        &self,
+        province_id: ProvinceID,
+    ) -> Option<(ProvinceID, &Unit)> {
+        self.province_and_coasts(province_id)
+            .into_iter()
+            .find_map(|id| Some((id, self.province(id)?.occupied_by()?)))
+    }
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A single row of a province table. See `Map::from_province_table`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvinceRow {
+    pub province_id: ProvinceID,
+
+    pub province_name: String,
+
+    pub province_type: ProvinceType,
+
+    pub sc_value: Option<u8>,
+
+    pub core_of: Vec<PlayerID>,
+
+    pub owned_by: PlayerID,
+
+    // The province this row is a coast of, if any. Must come before this row in the table.
+    pub is_coast_of: Option<ProvinceID>,
+
+    pub occupied_by: Option<Unit>,
+}
+
+impl ProvinceRow {
+    // Reads a table of provinces from CSV or TSV text, laid out like `maps/standard_map.csv`: a header naming the columns,
+    // then one row per province in order of their IDs. Only "Province Name" and "Province Type" are needed, and any of
+    // "Owned By", "SC Value", "Core Of", "Is Coast Of" and "Current Unit" can be left out or left empty. Other columns are
+    // ignored.
+    //
+    // Players are written by their names in `players` (several cores are separated by spaces), a coast's parent by its
+    // name, and a unit as its type and owner, i.e. "Army:England".
+    pub fn parse_table(input: &str, players: &[Player]) -> Result<Vec<Self>, MapError> {
+        let table = Table::parse(input);
+        let name_column = table.column("Province Name")?;
+        let type_column = table.column("Province Type")?;
+        let owner_column = table.column("Owned By").ok();
+        let sc_column = table.column("SC Value").ok();
+        let core_column = table.column("Core Of").ok();
+        let coast_column = table.column("Is Coast Of").ok();
+        let unit_column = table.column("Current Unit").ok();
+
+        let player_id = |name: &str| {
+            players
+                .iter()
+                .find(|player| player.player_name().eq_ignore_ascii_case(name))
+                .map(Player::player_id)
+        };
+
+        let mut rows: Vec<Self> = Vec::new();
+        for (line, fields) in &table.rows {
+            let invalid = MapError::InvalidRow(*line);
+            let field = |column: Option<usize>| {
+                column
+                    .and_then(|column| fields.get(column))
+                    .copied()
+                    .unwrap_or_default()
+            };
+
+            let province_name = field(Some(name_column));
+            let province_type = parse_province_type(field(Some(type_column))).ok_or(invalid)?;
+            let owned_by = match field(owner_column) {
+                "" => NEUTRAL,
+                owner => player_id(owner).ok_or(invalid)?,
+            };
+            let sc_value = match field(sc_column) {
+                "" => None,
+                value => Some(value.parse().map_err(|_| invalid)?),
+            };
+            let core_of = field(core_column)
+                .split_whitespace()
+                .map(|core| player_id(core).ok_or(invalid))
+                .collect::<Result<Vec<PlayerID>, MapError>>()?;
+            let is_coast_of = match field(coast_column) {
+                "" => None,
+                parent => rows
+                    .iter()
+                    .find(|row| row.province_name.eq_ignore_ascii_case(parent))
+                    .map(|row| row.province_id),
+            };
+            let occupied_by = match field(unit_column) {
+                "" => None,
+                unit => {
+                    let (unit_type, owner) = unit.split_once(':').ok_or(invalid)?;
+                    let unit_type = parse_unit_type(unit_type.trim()).map_err(|_| invalid)?;
+                    Some(Unit::new(
+                        player_id(owner.trim()).ok_or(invalid)?,
+                        unit_type,
+                    ))
+                }
+            };
+
+            if province_name.is_empty()
+                || (is_coast_of.is_none() && !field(coast_column).is_empty())
+            {
+                return Err(invalid);
+            }
+            rows.push(Self {
+                province_id: ProvinceID::try_from(rows.len()).map_err(|_| invalid)?,
+                province_name: province_name.to_string(),
+                province_type,
+                sc_value,
+                core_of,
+                owned_by,
+                is_coast_of,
+                occupied_by,
+            });
+        }
+
+        Ok(rows)
+    }
+}
+
+/// A single row of a connection table. See `Map::add_connection_table`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionRow {
+    pub province_1_id: ProvinceID,
+
+    pub province_2_id: ProvinceID,
+
+    pub allowed_unit_types: Vec<UnitType>,
+}
+
+impl ConnectionRow {
+    // Reads a table of connections between the provinces of `map` from CSV or TSV text, laid out like
+    // `maps/standard_map_connections.csv`: a header, then one row per connection with the "Province 1", "Province 2" and
+    // "Unit Types" columns. Provinces are written by their names, and the unit types that may use the connection are
+    // separated by spaces, i.e. "Army Fleet".
+    pub fn parse_table(input: &str, map: &Map) -> Result<Vec<Self>, MapError> {
+        let table = Table::parse(input);
+        let columns = [
+            table.column("Province 1")?,
+            table.column("Province 2")?,
+            table.column("Unit Types")?,
+        ];
+
+        table
+            .rows
+            .iter()
+            .map(|(line, fields)| {
+                let invalid = MapError::InvalidRow(*line);
+                let [province_1, province_2, unit_types] =
+                    columns.map(|column| fields.get(column).copied().unwrap_or_default());
+                let province = |name: &str| map.province_by_name(name).ok_or(invalid);
+
+                Ok(Self {
+                    province_1_id: province(province_1)?,
+                    province_2_id: province(province_2)?,
+                    allowed_unit_types: unit_types
+                        .split_whitespace()
+                        .map(|unit_type| parse_unit_type(unit_type).map_err(|_| invalid))
+                        .collect::<Result<Vec<UnitType>, MapError>>()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Counts of what is on a map. See `Map::stats`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapStats {
+    // Every province, including coasts.
+    pub province_count: usize,
+
+    pub sc_count: usize,
+
+    // The number of provinces of each type. Coasts are only the coasts of provinces with split coasts.
+    pub land: usize,
+    pub coast: usize,
+    pub water: usize,
+    pub deepsea: usize,
+
+    pub connection_count: usize,
+}
+
+/// Neighbors of every province on a map, for each unit type. See `Map::build_adjacency`.
+pub struct Adjacency {
+    // Both are indexed by province ID.
+    army_neighbors: Vec<Vec<ProvinceID>>,
+    fleet_neighbors: Vec<Vec<ProvinceID>>,
+}
+
+impl Adjacency {
+    pub fn neighbors(&self, province_id: ProvinceID, unit_type: &UnitType) -> &[ProvinceID] {
+        let neighbors = match unit_type {
+            UnitType::Army => &self.army_neighbors,
+            UnitType::Fleet => &self.fleet_neighbors,
+        };
+
+        neighbors
+            .get(province_id as usize)
+            .map(|neighbors| neighbors.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+/// Remembers the convoy routes found by `Map::convoy_routes`, so that they don't have to be searched for again when the
+/// same convoy is looked at many times (i.e. while resolving a turn with several convoys).
+///
+/// Routes are kept for each `from` and `to` along with the set of convoying fleets, so a different set of fleets is always
+/// searched for again, and the order the fleets are listed in doesn't matter. Routes only depend on the map's provinces and
+/// connections, and on the fleets given, so units moving around doesn't make them stale. The planner doesn't know which
+/// map it was used with though: it must be cleared (or a new one made) whenever provinces or connections are changed, or
+/// before using it with a different map.
+#[derive(Clone, Debug, Default)]
+pub struct ConvoyPlanner {
+    routes: HashMap<(ProvinceID, ProvinceID, Vec<ProvinceID>), Vec<Vec<ProvinceID>>>,
+}
+
+impl ConvoyPlanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The same as `Map::convoy_routes`, only searching the map if these routes haven't been asked for before.
+    pub fn convoy_routes(
+        &mut self,
+        map: &Map,
+        from: ProvinceID,
+        to: ProvinceID,
+        convoying_fleets: &[ProvinceID],
+    ) -> &[Vec<ProvinceID>] {
+        let mut fleet_set = convoying_fleets.to_vec();
+        fleet_set.sort();
+        fleet_set.dedup();
+
+        self.routes
+            .entry((from, to, fleet_set))
+            .or_insert_with(|| map.convoy_routes(from, to, convoying_fleets))
+    }
+
+    // The number of different convoys that have routes remembered.
+    pub fn cached_count(&self) -> usize {
+        self.routes.len()
+    }
+
+    // Forgets every route, i.e. after the map's connections have changed.
+    pub fn clear(&mut self) {
+        self.routes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Order;
+
+    fn row(
+        province_id: ProvinceID,
+        province_name: &str,
+        province_type: ProvinceType,
+        is_coast_of: Option<ProvinceID>,
+    ) -> ProvinceRow {
+        ProvinceRow {
+            province_id,
+            province_name: province_name.to_string(),
+            province_type,
+            sc_value: None,
+            core_of: Vec::new(),
+            owned_by: NEUTRAL,
+            is_coast_of,
+            occupied_by: None,
+        }
+    }
+
+    // The ID of the province called `name`, which must be on the map.
+    fn id(map: &Map, name: &str) -> ProvinceID {
+        map.province_by_name(name)
+            .unwrap_or_else(|| panic!("{name} should be on the map"))
+    }
+
+    // The names of the provinces, sorted so that they don't depend on the order the map lists them in.
+    fn names(map: &Map, provinces: &[ProvinceID]) -> Vec<String> {
+        let mut names: Vec<String> = provinces
+            .iter()
+            .map(|province| map.province(*province).unwrap().province_name().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn players() -> Vec<Player> {
+        vec![
+            Player::new(1, "France".to_string()),
+            Player::new(2, "Spain".to_string()),
+        ]
+    }
+
+    const PROVINCE_CSV: &str = "\
+Province Name,Province Type,Owned By,SC Value,Core Of,Is Coast Of,Current Unit
+MAO,Water,,,,,Fleet:France
+Spa,Land,Spain,1,Spain,,
+Spa NC,Coast,,0,,Spa,
+Spa SC,Coast,,0,,Spa,
+Por,Land,,1,,,Army:Spain
+";
+
+    const CONNECTION_CSV: &str = "\
+Province 1,Province 2,Unit Types
+MAO,Spa NC,Fleet
+MAO,Spa SC,Fleet
+MAO,Por,Fleet
+Spa,Por,Army
+";
+
+    #[test]
+    fn coasts_never_count_as_supply_centers() {
+        // Spain's coasts, given an `sc_value` of 0, may be built on because Spain is a supply center.
+        assert!(Map::from_tables(PROVINCE_CSV, CONNECTION_CSV, &players()).is_ok());
+
+        let counting_coast = PROVINCE_CSV.replace("Spa NC,Coast,,0,,Spa,", "Spa NC,Coast,,1,,Spa,");
+        assert_eq!(
+            Map::from_tables(&counting_coast, CONNECTION_CSV, &players()).err(),
+            Some(MapError::CoastSupplyCenter(2))
+        );
+
+        let not_a_center = PROVINCE_CSV.replace("Spa,Land,Spain,1,Spain,,", "Spa,Land,,,,,");
+        assert_eq!(
+            Map::from_tables(&not_a_center, CONNECTION_CSV, &players()).err(),
+            Some(MapError::CoastSupplyCenter(2))
+        );
+    }
+
+    #[test]
+    fn province_table_builds_provinces_in_order() {
+        let mut spain = row(1, "Spa", ProvinceType::Land, None);
+        spain.sc_value = Some(1);
+        spain.core_of = vec![2];
+        spain.owned_by = 2;
+        let rows = vec![
+            row(0, "MAO", ProvinceType::Water, None),
+            spain,
+            row(2, "Spa NC", ProvinceType::Coast, Some(1)),
+            row(3, "Spa SC", ProvinceType::Coast, Some(1)),
+            row(4, "Por", ProvinceType::Land, None),
+        ];
+
+        let map = Map::from_province_table(rows.into_iter()).unwrap();
+
+        assert_eq!(map.province_count(), 5);
+        let spain = map.province(1).unwrap();
+        assert_eq!(spain.province_name(), "Spa");
+        assert_eq!(spain.owned_by(), 2);
+        assert_eq!(spain.core_of(), &[2]);
+        assert!(spain.is_supply_center());
+        assert_eq!(spain.has_coasts(), &[2, 3]);
+        assert_eq!(map.province(3).unwrap().is_coast_of(), Some(1));
+        assert_eq!(map.province_by_name("Por"), Some(4));
+        assert_eq!(map.validate(), Ok(()));
+    }
+
+    #[test]
+    fn province_table_rejects_rows_out_of_order() {
+        let rows = vec![
+            row(0, "MAO", ProvinceType::Water, None),
+            row(2, "Spa", ProvinceType::Land, None),
+        ];
+        assert_eq!(
+            Map::from_province_table(rows.into_iter()).err(),
+            Some(MapError::ProvinceOutOfOrder(2))
+        );
+
+        let rows = vec![
+            row(0, "Spa NC", ProvinceType::Coast, Some(1)),
+            row(1, "Spa", ProvinceType::Land, None),
+        ];
+        assert_eq!(
+            Map::from_province_table(rows.into_iter()).err(),
+            Some(MapError::UnknownProvince(1))
+        );
+    }
+
+    #[test]
+    fn province_table_parses_csv_and_tsv() {
+        let rows = ProvinceRow::parse_table(PROVINCE_CSV, &players()).unwrap();
+
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[1].owned_by, 2);
+        assert_eq!(rows[1].sc_value, Some(1));
+        assert_eq!(rows[1].core_of, vec![2]);
+        assert_eq!(rows[3].is_coast_of, Some(1));
+        assert_eq!(rows[0].occupied_by, Some(Unit::new(1, UnitType::Fleet)));
+        assert_eq!(rows[4].occupied_by, Some(Unit::new(2, UnitType::Army)));
+
+        let tsv = PROVINCE_CSV.replace(',', "\t");
+        assert_eq!(ProvinceRow::parse_table(&tsv, &players()).unwrap(), rows);
+    }
+
+    #[test]
+    fn province_table_reports_the_bad_row() {
+        let unknown_owner = PROVINCE_CSV.replace("Fleet:France", "Fleet:Germany");
+        assert_eq!(
+            ProvinceRow::parse_table(&unknown_owner, &players()),
+            Err(MapError::InvalidRow(2))
+        );
+
+        let coast_first = "Province Name,Province Type,Is Coast Of\nSpa NC,Coast,Spa\nSpa,Land,\n";
+        assert_eq!(
+            ProvinceRow::parse_table(coast_first, &players()),
+            Err(MapError::InvalidRow(2))
+        );
+
+        assert_eq!(
+            ProvinceRow::parse_table("Province Name,SC Value\nSpa,1\n", &players()),
+            Err(MapError::MissingColumn("Province Type"))
+        );
+    }
+
+    #[test]
+    fn standard_map_loads_the_starting_position() {
+        let map = Map::standard();
+        let name = |id: ProvinceID| map.province(id).unwrap().province_name().to_string();
+        let mut army_neighbors: Vec<String> = map
+            .neighbors(map.province_by_name("Par").unwrap(), &UnitType::Army)
+            .into_iter()
+            .map(name)
+            .collect();
+        army_neighbors.sort();
+
+        assert_eq!(map.province_count(), 81);
+        assert_eq!(map.connections().count(), 218);
+        assert_eq!(map.occupied().count(), 22);
+        assert_eq!(map.validate(), Ok(()));
+        assert_eq!(army_neighbors, vec!["Bre", "Bur", "Gas", "Pic"]);
+
+        // Russia's southern fleet starts on the south coast of St. Petersburg.
+        let stp_sc = map.province_by_name("StP/sc").unwrap();
+        assert_eq!(
+            map.province(stp_sc).unwrap().occupied_by(),
+            Some(&Unit::new(6, UnitType::Fleet))
+        );
+        assert_eq!(
+            map.occupant_owner(map.province_by_name("Lvp").unwrap()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn map_from_tables_adds_connections() {
+        let map = Map::from_tables(PROVINCE_CSV, CONNECTION_CSV, &players()).unwrap();
+        let spa_nc = map.province_by_name("Spa/nc").unwrap();
+
+        assert_eq!(map.connections().count(), 4);
+        assert!(map.neighbors(0, &UnitType::Fleet).contains(&spa_nc));
+        assert_eq!(map.neighbors(4, &UnitType::Army), vec![1]);
+
+        let unknown_province = CONNECTION_CSV.replace("MAO,Por", "MAO,Gas");
+        assert_eq!(
+            Map::from_tables(PROVINCE_CSV, &unknown_province, &players()).err(),
+            Some(MapError::InvalidRow(4))
+        );
+    }
+
+    #[test]
+    fn coast_connections_lists_each_coast_with_its_fleet_neighbors() {
+        let map = Map::standard();
+        let coasts = map.coast_connections(id(&map, "Spa"));
+
+        assert_eq!(coasts.len(), 2);
+        assert_eq!(coasts[0].0, id(&map, "Spa/nc"));
+        assert_eq!(names(&map, &coasts[0].1), vec!["Gas", "MAO", "Por"]);
+        assert_eq!(coasts[1].0, id(&map, "Spa/sc"));
+        assert_eq!(
+            names(&map, &coasts[1].1),
+            vec!["LYO", "MAO", "Mar", "Por", "WES"]
+        );
+
+        // A province without split coasts has none to list.
+        assert!(map.coast_connections(id(&map, "Por")).is_empty());
+    }
+
+    #[test]
+    fn parent_of_coast_is_only_given_for_coasts() {
+        let map = Map::standard();
+
+        assert_eq!(
+            map.parent_of_coast(id(&map, "Spa/sc")),
+            Some(id(&map, "Spa"))
+        );
+        assert_eq!(map.parent_of_coast(id(&map, "Spa")), None);
+        assert_eq!(map.parent_of_coast(id(&map, "MAO")), None);
+    }
+
+    #[test]
+    fn a_unit_in_a_province_occupies_its_coasts() {
+        let mut map = Map::standard();
+        let (spa, spa_nc, spa_sc) = (id(&map, "Spa"), id(&map, "Spa/nc"), id(&map, "Spa/sc"));
+        assert!(!map.is_province_occupied_including_coasts(spa_nc));
+
+        map.province_mut(spa)
+            .unwrap()
+            .set_occupant(Some(Unit::new(3, UnitType::Army)));
+        assert!(map.is_province_occupied_including_coasts(spa));
+        assert!(map.is_province_occupied_including_coasts(spa_nc));
+        assert!(map.is_province_occupied_including_coasts(spa_sc));
+        assert_eq!(map.occupant_owner(spa_nc), Some(3));
+
+        // The same goes the other way, for a fleet on one of the coasts.
+        map.province_mut(spa).unwrap().set_occupant(None);
+        map.province_mut(spa_sc)
+            .unwrap()
+            .set_occupant(Some(Unit::new(5, UnitType::Fleet)));
+        assert!(map.is_province_occupied_including_coasts(spa));
+        assert!(map.is_province_occupied_including_coasts(spa_nc));
+        assert!(!map.is_province_occupied_including_coasts(id(&map, "Por")));
+    }
+
+    #[test]
+    fn convoy_routes_follow_a_long_chain_of_fleets() {
+        let map = Map::standard();
+        let ids = |names: &[&str]| names.iter().map(|name| id(&map, name)).collect::<Vec<_>>();
+        // The fleets in Skagerrak and the Black Sea aren't on the way, and the fleet in Brest is on a coast.
+        let fleets = ids(&["SKA", "ENG", "Bre", "MAO", "WES", "TYS", "BLA"]);
+
+        assert_eq!(
+            map.convoy_routes(id(&map, "Lon"), id(&map, "Nap"), &fleets),
+            vec![ids(&["ENG", "MAO", "WES", "TYS"])]
+        );
+
+        // There is no limit on how long a chain can be.
+        let fleets = ids(&["ENG", "MAO", "WES", "TYS", "ION", "EAS"]);
+        assert_eq!(
+            map.convoy_routes(id(&map, "Lon"), id(&map, "Syr"), &fleets),
+            vec![fleets.clone()]
+        );
+        assert!(
+            map.convoy_routes(id(&map, "Lon"), id(&map, "Syr"), &fleets[..5])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn home_centers_ignore_who_owns_them_now() {
+        let mut map = Map::standard();
+        let paris = id(&map, "Par");
+        map.province_mut(paris).unwrap().set_owner(4);
+
+        assert_eq!(names(&map, &map.home_centers(3)), vec!["Bre", "Mar", "Par"]);
+        assert_eq!(
+            names(&map, &map.home_centers(6)),
+            vec!["Mos", "Sev", "Stp", "War"]
+        );
+        // Germany owns Paris, but can't build there.
+        assert!(!map.home_centers(4).contains(&paris));
+        assert!(map.home_centers(NEUTRAL).is_empty());
+    }
+
+    #[test]
+    fn adjacency_matches_neighbors() {
+        let map = Map::standard();
+        let adjacency = map.build_adjacency();
+
+        for province in map.provinces() {
+            for unit_type in [UnitType::Army, UnitType::Fleet] {
+                assert_eq!(
+                    adjacency.neighbors(province.province_id(), &unit_type),
+                    map.neighbors(province.province_id(), &unit_type).as_slice(),
+                    "{unit_type:?} neighbors of {}",
+                    province.province_name()
+                );
+            }
+        }
+        // A province that isn't on the map has no neighbors.
+        assert!(adjacency.neighbors(200, &UnitType::Army).is_empty());
+    }
+
+    #[test]
+    fn occupied_lists_every_unit_with_its_province() {
+        let map = Map::standard();
+        let austrian: Vec<ProvinceID> = map
+            .occupied()
+            .filter(|(_, unit)| unit.owner() == 1)
+            .map(|(id, _)| id)
+            .collect();
+        let fleets: Vec<ProvinceID> = map
+            .occupied()
+            .filter(|(_, unit)| unit.get_type() == UnitType::Fleet)
+            .map(|(id, _)| id)
+            .collect();
+
+        assert_eq!(names(&map, &austrian), vec!["Bud", "Tri", "Vie"]);
+        assert_eq!(fleets.len(), 9);
+        // Provinces are listed in order of their IDs.
+        assert!(
+            map.provinces()
+                .enumerate()
+                .all(|(index, province)| province.province_id() as usize == index)
+        );
+    }
+
+    // Two coastal provinces on either side of an ocean, with deep sea in the middle of it.
+    const OCEAN_CSV: &str = "\
+Province Name,Province Type,Current Unit
+West,Land,Army:France
+Shallows,Water,Fleet:France
+Abyss,DeepSea,Fleet:France
+Reef,Water,Fleet:France
+East,Land,
+";
+
+    const OCEAN_CONNECTION_CSV: &str = "\
+Province 1,Province 2,Unit Types
+West,Shallows,Fleet
+Shallows,Abyss,Fleet
+Abyss,Reef,Fleet
+Reef,East,Fleet
+";
+
+    // France's army in Home can only reach Spain's center in Prize through North or South.
+    const LINE_CSV: &str = "\
+Province Name,Province Type,Owned By,SC Value,Current Unit
+Home,Land,France,1,Army:France
+North,Land,,,
+South,Land,,,
+Prize,Land,Spain,1,
+";
+
+    const LINE_CONNECTION_CSV: &str = "\
+Province 1,Province 2,Unit Types
+Home,North,Army
+Home,South,Army
+North,Prize,Army
+South,Prize,Army
+";
+
+    #[test]
+    fn holding_both_ways_through_cuts_a_player_off() {
+        let map = Map::from_tables(LINE_CSV, LINE_CONNECTION_CSV, &players()).unwrap();
+        let (north, south) = (id(&map, "North"), id(&map, "South"));
+
+        assert!(map.is_cut(&[north, south], 1));
+        assert!(!map.is_cut(&[north], 1));
+        assert!(!map.is_cut(&[], 1));
+        // Spain has no units at all, so has nothing to get through with.
+        assert!(map.is_cut(&[], 2));
+    }
+
+    #[test]
+    fn ends_of_a_convoy_are_never_part_of_its_route() {
+        let map = Map::standard();
+        let ids = |names: &[&str]| names.iter().map(|name| id(&map, name)).collect::<Vec<_>>();
+
+        // Fleets in London and Belgium are listed as convoying the army from one to the other, so only the fleet in the
+        // North Sea is left to carry it.
+        assert_eq!(
+            map.convoy_routes(
+                id(&map, "Lon"),
+                id(&map, "Bel"),
+                &ids(&["Lon", "NTH", "Bel"])
+            ),
+            vec![ids(&["NTH"])]
+        );
+        assert!(
+            map.convoy_routes(id(&map, "Lon"), id(&map, "Bel"), &ids(&["Lon", "Bel"]))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn convoy_planner_remembers_routes_for_each_set_of_fleets() {
+        let map = Map::standard();
+        let ids = |names: &[&str]| names.iter().map(|name| id(&map, name)).collect::<Vec<_>>();
+        let (lon, nap) = (id(&map, "Lon"), id(&map, "Nap"));
+        let fleets = ids(&["ENG", "MAO", "WES", "TYS"]);
+        let mut planner = ConvoyPlanner::new();
+
+        assert_eq!(
+            planner.convoy_routes(&map, lon, nap, &fleets),
+            map.convoy_routes(lon, nap, &fleets).as_slice()
+        );
+        assert_eq!(planner.cached_count(), 1);
+
+        // The same fleets in another order are the same convoy.
+        let reversed: Vec<ProvinceID> = fleets.iter().rev().copied().collect();
+        assert_eq!(
+            planner.convoy_routes(&map, lon, nap, &reversed),
+            std::slice::from_ref(&fleets)
+        );
+        assert_eq!(planner.cached_count(), 1);
+
+        // Fewer fleets are searched for again, and can't make it.
+        assert!(
+            planner
+                .convoy_routes(&map, lon, nap, &fleets[..3])
+                .is_empty()
+        );
+        assert_eq!(planner.cached_count(), 2);
+
+        planner.clear();
+        assert_eq!(planner.cached_count(), 0);
+    }
+
+    #[test]
+    fn convoy_routes_pass_through_deep_sea() {
+        let map = Map::from_tables(OCEAN_CSV, OCEAN_CONNECTION_CSV, &players()).unwrap();
+        let (west, abyss, east) = (id(&map, "West"), id(&map, "Abyss"), id(&map, "East"));
+        let fleets = [id(&map, "Shallows"), abyss, id(&map, "Reef")];
+
+        assert_eq!(
+            map.convoy_routes(west, east, &fleets),
+            vec![fleets.to_vec()]
+        );
+        assert!(
+            map.neighbors(abyss, &UnitType::Fleet)
+                .contains(&id(&map, "Reef"))
+        );
+    }
+
+    #[test]
+    fn army_cannot_be_convoyed_into_deep_sea() {
+        let map = Map::from_tables(OCEAN_CSV, OCEAN_CONNECTION_CSV, &players()).unwrap();
+        let (west, abyss) = (id(&map, "West"), id(&map, "Abyss"));
+
+        assert!(
+            map.convoy_routes(west, abyss, &[id(&map, "Shallows")])
+                .is_empty()
+        );
+        assert!(!Order::move_order(west, abyss).is_valid_for(&map));
+        assert!(Order::move_order(west, id(&map, "East")).is_valid_for(&map));
+        assert!(Order::move_order(id(&map, "Shallows"), abyss).is_valid_for(&map));
+    }
+
+    #[test]
+    fn shortest_path_finds_the_fewest_moves() {
+        let map = Map::standard();
+        let path = map
+            .shortest_path(id(&map, "Par"), id(&map, "Mun"), &UnitType::Army)
+            .unwrap();
+
+        assert_eq!(path.len(), 3);
+        assert_eq!(
+            (path[0], path[1], path[2]),
+            (id(&map, "Par"), id(&map, "Bur"), id(&map, "Mun"))
+        );
+        // Fleets need a longer way round to get from Brest to Kiel.
+        let fleet_path = map
+            .shortest_path(id(&map, "Bre"), id(&map, "Kie"), &UnitType::Fleet)
+            .unwrap();
+        assert_eq!(fleet_path.len(), 5);
+    }
+
+    #[test]
+    fn shortest_path_is_none_for_an_army_going_to_an_island() {
+        let map = Map::standard();
+
+        assert_eq!(
+            map.shortest_path(id(&map, "Par"), id(&map, "Lon"), &UnitType::Army),
+            None
+        );
+        assert_eq!(
+            map.shortest_path(id(&map, "Par"), id(&map, "ENG"), &UnitType::Army),
+            None
+        );
+    }
+
+    #[test]
+    fn shortest_path_to_the_same_province_is_just_that_province() {
+        let map = Map::standard();
+        let paris = id(&map, "Par");
+
+        assert_eq!(
+            map.shortest_path(paris, paris, &UnitType::Army),
+            Some(vec![paris])
+        );
+    }
+
+    #[test]
+    fn changing_a_cloned_map_leaves_the_original_alone() {
+        let map = Map::standard();
+        let (par, bur) = (id(&map, "Par"), id(&map, "Bur"));
+        let mut what_if = map.clone();
+        let army = what_if.province_mut(par).unwrap().take_occupant();
+        what_if.province_mut(bur).unwrap().set_occupant(army);
+
+        assert!(what_if.province(par).unwrap().occupied_by().is_none());
+        assert_eq!(what_if.occupant_owner(bur), Some(3));
+        assert_eq!(
+            map.province(par).unwrap().occupied_by(),
+            Some(&Unit::new(3, UnitType::Army))
+        );
+        assert!(map.province(bur).unwrap().occupied_by().is_none());
+    }
+
+    #[test]
+    fn provinces_are_found_by_name_ignoring_case() {
+        let map = Map::standard();
+        let paris = map.province_by_name("Par").unwrap();
+        assert_eq!(map.province(paris).unwrap().province_name(), "Par");
+        assert_eq!(map.province_by_name("PAR"), Some(paris));
+        assert_eq!(map.province_by_name("par"), Some(paris));
+    }
+
+    #[test]
+    fn a_coast_name_finds_the_coast_not_its_parent() {
+        let map = Map::standard();
+        let stp = map.province_by_name("StP").unwrap();
+        let north_coast = map.province_by_name("StP/nc").unwrap();
+
+        assert_ne!(north_coast, stp);
+        assert_eq!(map.province(north_coast).unwrap().is_coast_of(), Some(stp));
+        assert_eq!(map.province_by_name("stp/NC"), Some(north_coast));
+        assert_ne!(map.province_by_name("StP/sc"), Some(north_coast));
+    }
+
+    #[test]
+    fn unknown_names_find_nothing() {
+        let map = Map::standard();
+        assert_eq!(map.province_by_name("Atlantis"), None);
+        assert_eq!(map.province_by_name("Par/nc"), None);
+        assert_eq!(map.province_by_name("Atlantis/sc"), None);
+    }
+
+    #[test]
+    fn coastal_provinces_on_the_standard_map() {
+        let map = Map::standard();
+        assert!(map.is_coastal(id(&map, "Bre")));
+        assert!(!map.is_coastal(id(&map, "Par")));
+        assert!(!map.is_coastal(id(&map, "MAO")));
+
+        // A province with split coasts is coastal, but its coasts aren't.
+        assert!(map.is_coastal(id(&map, "Spa")));
+        assert!(!map.is_coastal(id(&map, "Spa/nc")));
+
+        let coastal = map.coastal_provinces();
+        assert!(coastal.contains(&id(&map, "Bre")));
+        assert!(coastal.contains(&id(&map, "Stp")));
+        assert!(!coastal.contains(&id(&map, "Mun")));
+        assert!(coastal.iter().all(|province| map.is_coastal(*province)));
+    }
+
+    #[test]
+    fn standard_map_has_34_supply_centers_owned_by_their_powers() {
+        let map = Map::standard();
+        let centers = map.supply_centers();
+        assert_eq!(centers.len(), 34);
+
+        let owner_of = |name: &str| {
+            let province = id(&map, name);
+            centers
+                .iter()
+                .find(|(center, _)| *center == province)
+                .map(|(_, owner)| *owner)
+        };
+        assert_eq!(owner_of("Par"), Some(3));
+        assert_eq!(owner_of("Lon"), Some(2));
+        assert_eq!(owner_of("Stp"), Some(6));
+        assert_eq!(owner_of("Con"), Some(7));
+        assert_eq!(owner_of("Bel"), Some(NEUTRAL));
+        assert_eq!(owner_of("Bur"), None);
+        assert_eq!(owner_of("Stp/sc"), None);
+        assert_eq!(
+            centers
+                .iter()
+                .filter(|(_, owner)| *owner != NEUTRAL)
+                .count(),
+            22
+        );
+    }
+
+    #[test]
+    fn only_fleets_into_a_province_with_split_coasts_must_choose_one() {
+        let map = Map::standard();
+        for name in ["Spa", "Bul", "StP"] {
+            assert!(
+                map.requires_coast_choice(id(&map, name), &UnitType::Fleet),
+                "{name}"
+            );
+            assert!(
+                !map.requires_coast_choice(id(&map, name), &UnitType::Army),
+                "{name}"
+            );
         }
+        // Naming the coast is the choice, and other coastal provinces only have the one coast.
+        assert!(!map.requires_coast_choice(id(&map, "Spa/nc"), &UnitType::Fleet));
+        assert!(!map.requires_coast_choice(id(&map, "Por"), &UnitType::Fleet));
+        assert!(!map.requires_coast_choice(id(&map, "MAO"), &UnitType::Fleet));
+    }
+
+    #[test]
+    fn connection_is_only_followed_by_units_that_can_be_at_both_ends() {
+        let mut map = Map::standard();
+        let (par, eng, bre) = (id(&map, "Par"), id(&map, "ENG"), id(&map, "Bre"));
+        // A mistake in a variant: armies allowed between Paris and the English Channel.
+        map.add_connection(Connection::army(par, eng).unwrap());
+
+        assert!(!map.neighbors(par, &UnitType::Army).contains(&eng));
+        assert!(map.neighbors(eng, &UnitType::Army).is_empty());
+        assert!(!map.are_adjacent(par, eng));
+
+        assert!(map.are_adjacent(par, bre));
+        assert!(map.are_adjacent(bre, eng));
+        assert!(!map.are_adjacent(par, id(&map, "Mun")));
+    }
+
+    #[test]
+    fn connections_of_a_province_leave_out_those_of_its_coasts() {
+        let map = Map::standard();
+        let other_ends = |name: &str| {
+            let province = id(&map, name);
+            let ends: Vec<ProvinceID> = map
+                .connections_of(province)
+                .iter()
+                .map(|connection| connection.other_end(province).unwrap())
+                .collect();
+            names(&map, &ends)
+        };
+
+        assert_eq!(other_ends("Par"), vec!["Bre", "Bur", "Gas", "Pic"]);
+        assert_eq!(other_ends("Spa"), vec!["Gas", "Mar", "Por"]);
+        assert_eq!(other_ends("Spa/nc"), vec!["Gas", "MAO", "Por"]);
+        assert!(
+            map.connections_of(id(&map, "Spa/nc"))
+                .iter()
+                .all(|connection| connection.allowed(&UnitType::Fleet)
+                    && !connection.allowed(&UnitType::Army))
+        );
+        assert!(map.connections_of(ProvinceID::MAX).is_empty());
+    }
+
+    #[test]
+    fn water_provinces_include_deep_sea_but_not_coasts() {
+        let map = Map::standard();
+        let water = map.water_provinces();
+
+        assert_eq!(water.len(), 19);
+        assert!(water.contains(&id(&map, "NTH")));
+        assert!(water.contains(&id(&map, "BOT")));
+        for name in ["Spa/nc", "StP/sc", "Lon", "Mun"] {
+            assert!(!water.contains(&id(&map, name)), "{name}");
+        }
+
+        // Deep sea counts as water too.
+        let map = Map::from_tables(OCEAN_CSV, OCEAN_CONNECTION_CSV, &players()).unwrap();
+        assert_eq!(
+            names(&map, &map.water_provinces()),
+            vec!["Abyss", "Reef", "Shallows"]
+        );
+    }
+
+    #[test]
+    fn army_in_york_reaches_further_by_convoy_than_by_land() {
+        let mut map = Map::standard();
+        let (yor, nth) = (id(&map, "Yor"), id(&map, "NTH"));
+        map.province_mut(yor)
+            .unwrap()
+            .set_occupant(Some(Unit::new(2, UnitType::Army)));
+
+        assert_eq!(
+            names(&map, &map.direct_neighbors(yor)),
+            vec!["Edi", "Lon", "Lvp", "Wal"]
+        );
+        // Edinburgh and London can be reached by land, so aren't listed again.
+        assert_eq!(
+            names(&map, &map.convoy_reachable(yor, &[nth])),
+            vec!["Bel", "Den", "Hol", "Nwy"]
+        );
+        assert!(map.convoy_reachable(yor, &[]).is_empty());
+
+        // Fleets aren't convoyed, and there is nothing to move out of an empty province.
+        assert!(map.convoy_reachable(id(&map, "Lon"), &[nth]).is_empty());
+        assert!(!map.direct_neighbors(id(&map, "Lon")).is_empty());
+        assert!(map.direct_neighbors(id(&map, "Wal")).is_empty());
+    }
+
+    #[test]
+    fn connection_constructors_set_who_can_use_them() {
+        let fleet = Connection::fleet(3, 1).unwrap();
+        assert!(fleet.allowed(&UnitType::Fleet) && !fleet.allowed(&UnitType::Army));
+        assert_eq!(fleet.other_end(1), Some(3));
+
+        let army = Connection::army(1, 3).unwrap();
+        assert!(army.allowed(&UnitType::Army) && !army.allowed(&UnitType::Fleet));
+
+        let both = Connection::both(1, 3).unwrap();
+        assert!(both.allowed(&UnitType::Army) && both.allowed(&UnitType::Fleet));
+
+        assert_eq!(
+            Connection::fleet(2, 2).err(),
+            Some(ConnectionError::SameProvince(2))
+        );
+        assert_eq!(
+            Connection::army(2, 2).err(),
+            Some(ConnectionError::SameProvince(2))
+        );
+        assert_eq!(
+            Connection::both(2, 2).err(),
+            Some(ConnectionError::SameProvince(2))
+        );
+    }
+
+    #[test]
+    fn connections_can_be_added_edited_and_removed() {
+        let mut map = Map::standard();
+        let (par, mun) = (id(&map, "Par"), id(&map, "Mun"));
+        assert!(!map.are_adjacent(par, mun));
+        assert!(!map.set_connection_unit_types(par, mun, vec![UnitType::Army]));
+
+        map.add_connection(Connection::new(par, mun, vec![UnitType::Army]).unwrap());
+        assert!(map.are_adjacent(par, mun));
+        assert!(map.are_adjacent(mun, par));
+
+        // Only fleets may use it now, so the provinces are still adjacent, but not for armies.
+        assert!(map.set_connection_unit_types(mun, par, vec![UnitType::Fleet]));
+        assert!(map.are_adjacent(par, mun));
+        assert!(!map.neighbors(par, &UnitType::Army).contains(&mun));
+        assert!(map.neighbors(par, &UnitType::Fleet).contains(&mun));
+        assert!(map.set_connection_unit_types(par, mun, vec![UnitType::Army]));
+        assert!(map.are_adjacent(par, mun));
+
+        assert!(map.remove_connection(mun, par));
+        assert!(!map.are_adjacent(par, mun));
+        assert!(!map.remove_connection(par, mun));
+        assert!(map.are_adjacent(par, id(&map, "Bur")));
+    }
+
+    #[test]
+    fn occupant_owner_treats_coasts_as_their_parent() {
+        let map = Map::standard();
+        assert_eq!(map.occupant_owner(id(&map, "Par")), Some(3));
+
+        // Russia's fleet is on St Petersburg's south coast, so it is found from the parent and from either coast.
+        assert_eq!(map.occupant_owner(id(&map, "Stp")), Some(6));
+        assert_eq!(map.occupant_owner(id(&map, "Stp/sc")), Some(6));
+        assert_eq!(map.occupant_owner(id(&map, "Stp/nc")), Some(6));
+
+        assert_eq!(map.occupant_owner(id(&map, "Bur")), None);
+        assert_eq!(map.occupant_owner(id(&map, "Spa/nc")), None);
+    }
+
+    #[test]
+    fn stats_count_the_standard_map() {
+        // 75 provinces plus the two coasts each of Spain, Bulgaria and St Petersburg. Land counts the 14 inland and 42
+        // coastal provinces alike.
+        assert_eq!(
+            Map::standard().stats(),
+            MapStats {
+                province_count: 81,
+                sc_count: 34,
+                land: 56,
+                coast: 6,
+                water: 19,
+                deepsea: 0,
+                connection_count: 218,
+            }
+        );
     }
 }
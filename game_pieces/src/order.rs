@@ -6,14 +6,22 @@ use petgraph::{
     graph::NodeIndex,
     visit::EdgeRef,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt, ops::ControlFlow};
 
-use crate::province::ProvinceID;
+use crate::{
+    error::{AdjudicationError, OrderError},
+    map::{ConvoyPlanner, Map},
+    parse::{parse_province, parse_unit_type, tokenize},
+    player::{NEUTRAL, PlayerID},
+    province::ProvinceID,
+    unit::{Unit, UnitType},
+    variant::{ParadoxRule, VariantConfig},
+};
 
 type OrderGraph = Graph<(), (), Directed>;
 type IndexMap<'a> = HashMap<NodeIndex, Order>;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum OrderType {
     // These are legal orders for players to give:
     Hold,
@@ -31,11 +39,32 @@ pub enum OrderType {
 
     // A unit that was offering support was moved into by another player.
     SupportCut,
+
+    // A move that failed to reach its destination (i.e. it bounced, or was blocked by a stronger unit).
+    Bounced,
+}
+
+// The order types given during resolution are written as the order they were updated from, where there is one, i.e.
+// "Support (cut)".
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Hold => "Hold",
+            Self::Move => "Move",
+            Self::Support => "Support",
+            Self::Convoy => "Convoy",
+            Self::IllegalOrder => "Illegal order",
+            Self::RequiredOrderNotGiven => "Required order not given",
+            Self::SupportCut => "Support (cut)",
+            Self::Bounced => "Move (bounced)",
+        };
+        write!(f, "{name}")
+    }
 }
 
 use OrderType::*;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Order {
     // The order the the player gave for this province
     original_order_type: OrderType,
@@ -52,9 +81,17 @@ pub struct Order {
     // The destination for the order.
     order_to: ProvinceID,
 
-    // The strength of an order. The default, and by far most common value, is 1. This can only be increased with supports.
+    // Whether the player explicitly asked for this move to be convoyed (i.e. "A Lon - Nwy via Convoy"). Only used for moves.
+    convoy_intended: bool,
+
+    // The strength of an order. The default, and by far most common value, is 1. This can only be increased with supports,
+    // and only supports that weren't cut count, so an order supported by five units with two of them cut has strength 4.
+    // Each supporting unit only counts once, so a `u8` is plenty for any real board.
     order_strength: u8,
 
+    // The provinces of the units whose support was counted in `order_strength`.
+    supported_by: ProvinceSet,
+
     // Whether or not this order has been fully resolved.
     resolved: bool,
 
@@ -63,6 +100,241 @@ pub struct Order {
 }
 
 impl Order {
+    // Creates a new, unresolved order. For `Hold` orders, `order_from` and `order_to` should both be `order_of`, and for `Move`
+    // orders `order_from` should be `order_of`.
+    pub fn new(
+        order_type: OrderType,
+        order_of: ProvinceID,
+        order_from: ProvinceID,
+        order_to: ProvinceID,
+    ) -> Self {
+        Self {
+            original_order_type: order_type,
+            order_type,
+            order_of,
+            order_from,
+            order_to,
+            convoy_intended: false,
+            order_strength: 1,
+            supported_by: ProvinceSet::default(),
+            resolved: false,
+            dislodged: false,
+        }
+    }
+
+    // A unit holding in `province`.
+    pub fn hold(province: ProvinceID) -> Self {
+        Self::new(Hold, province, province, province)
+    }
+
+    // A unit moving from `from` to `to`.
+    pub fn move_order(from: ProvinceID, to: ProvinceID) -> Self {
+        Self::new(Move, from, from, to)
+    }
+
+    // The unit in `at` supporting the unit in `from` to move to `to`. For a support hold, `to` is the same as `from`.
+    pub fn support(at: ProvinceID, from: ProvinceID, to: ProvinceID) -> Self {
+        Self::new(Support, at, from, to)
+    }
+
+    // The fleet in `at` convoying the army in `from` to `to`.
+    pub fn convoy(at: ProvinceID, from: ProvinceID, to: ProvinceID) -> Self {
+        Self::new(Convoy, at, from, to)
+    }
+
+    // Parses an order such as "A Par H", "A Par - Bur", "A Lon - Nwy via Convoy" (or "VC"), "A Mun S A Par - Bur",
+    // "A Mun S A Bur" (supporting a hold, optionally followed by "H"), or "F NTH C A Lon - Nwy".
+    pub fn parse(input: &str, map: &Map) -> Result<Self, OrderError> {
+        let mut tokens = tokenize(input);
+
+        let convoy_intended = match tokens.as_slice() {
+            [.., via, convoy]
+                if via.eq_ignore_ascii_case("via") && convoy.eq_ignore_ascii_case("convoy") =>
+            {
+                tokens.truncate(tokens.len() - 2);
+                true
+            }
+            [.., vc] if vc.eq_ignore_ascii_case("vc") => {
+                tokens.truncate(tokens.len() - 1);
+                true
+            }
+            _ => false,
+        };
+
+        let is = |token: &String, names: &[&str]| {
+            names.iter().any(|name| token.eq_ignore_ascii_case(name))
+        };
+
+        let order = match tokens.as_slice() {
+            [unit_type, at, hold] if is(hold, &["h", "hold"]) => {
+                parse_unit_type(unit_type)?;
+                Self::hold(parse_province(at, map)?)
+            }
+            [unit_type, from, dash, to] if dash == "-" => {
+                let unit_type = parse_unit_type(unit_type)?;
+                let from = parse_province(from, map)?;
+                let mut order = Self::move_order(from, parse_province(to, map)?);
+                order.convoy_intended = convoy_intended;
+
+                // Only armies can be convoyed.
+                if convoy_intended && !unit_in(map, from, unit_type).can_be_convoyed() {
+                    return Err(OrderError::UnitCannotPerform(from));
+                }
+                return Ok(order.with_unit_locations(map));
+            }
+            [unit_type, at, support, supported_type, supported]
+            | [unit_type, at, support, supported_type, supported, _]
+                if is(support, &["s", "support", "supports"])
+                    && tokens.get(5).is_none_or(|hold| is(hold, &["h", "hold"])) =>
+            {
+                parse_unit_type(unit_type)?;
+                parse_unit_type(supported_type)?;
+                let supported = parse_province(supported, map)?;
+                Self::support(parse_province(at, map)?, supported, supported)
+            }
+            [unit_type, at, kind, other_type, from, dash, to] if dash == "-" => {
+                let order_type = if is(kind, &["s", "support", "supports"]) {
+                    Support
+                } else if is(kind, &["c", "convoy", "convoys"]) {
+                    Convoy
+                } else {
+                    return Err(OrderError::InvalidFormat(input.to_string()));
+                };
+
+                let unit_type = parse_unit_type(unit_type)?;
+                let other_type = parse_unit_type(other_type)?;
+                let order = Self::new(
+                    order_type,
+                    parse_province(at, map)?,
+                    parse_province(from, map)?,
+                    parse_province(to, map)?,
+                );
+
+                // Only fleets can convoy, and only armies can be convoyed.
+                if order_type == Convoy {
+                    if !unit_in(map, order.order_of, unit_type).can_convoy() {
+                        return Err(OrderError::UnitCannotPerform(order.order_of));
+                    }
+                    if !unit_in(map, order.order_from, other_type).can_be_convoyed() {
+                        return Err(OrderError::UnitCannotPerform(order.order_from));
+                    }
+                }
+                order
+            }
+            _ => return Err(OrderError::InvalidFormat(input.to_string())),
+        };
+
+        // Only moves can be convoyed.
+        if convoy_intended {
+            return Err(OrderError::InvalidFormat(input.to_string()));
+        }
+
+        Ok(order.with_unit_locations(map))
+    }
+
+    // The same order, but naming the exact province or coast that each unit it refers to is on, i.e. "F Spa - MAO" for a
+    // fleet on Spain's north coast is the order for Spa/nc. The coast written in an order for a unit is ignored, as a unit
+    // can only ever be in one place (DATC 6.B.10). Provinces without a unit, and the destination of a move, are left as
+    // they are.
+    fn with_unit_locations(mut self, map: &Map) -> Self {
+        let location = |province| {
+            map.occupant_including_coasts(province)
+                .map_or(province, |(location, _)| location)
+        };
+
+        let support_hold = self.order_from == self.order_to;
+        self.order_of = location(self.order_of);
+        match self.order_type {
+            Hold => {
+                self.order_from = self.order_of;
+                self.order_to = self.order_of;
+            }
+            Move => self.order_from = self.order_of,
+            _ => {
+                self.order_from = location(self.order_from);
+                if support_hold {
+                    self.order_to = self.order_from;
+                }
+            }
+        }
+        self
+    }
+
+    // Writes the order out in the notation that `parse` reads, i.e. "A Par - Bur" or "F NTH C A Lon - Nwy", taking the unit
+    // types from the units on `map`. The order is written as it was given, not as it ended up after resolution.
+    pub fn format(&self, map: &Map) -> String {
+        let unit_type = |province| {
+            map.occupant_including_coasts(province)
+                .map(|(_, unit)| unit.get_type())
+        };
+        self.format_with(map, unit_type(self.order_of), unit_type(self.order_from))
+    }
+
+    // The same as `format`, but with the unit types given rather than taken from the map (i.e. once the units have moved on).
+    // `supported_type` is the type of the unit being supported, and is ignored for other orders.
+    pub(crate) fn format_with(
+        &self,
+        map: &Map,
+        unit_type: Option<UnitType>,
+        supported_type: Option<UnitType>,
+    ) -> String {
+        let name = |province: ProvinceID| {
+            map.province(province).map_or_else(
+                || province.to_string(),
+                |province| province.province_name().to_string(),
+            )
+        };
+        let letter = |unit_type| match unit_type {
+            Some(UnitType::Army) => "A",
+            Some(UnitType::Fleet) => "F",
+            None => "?",
+        };
+
+        let unit = format!("{} {}", letter(unit_type), name(self.order_of));
+        match self.original_order_type {
+            Move if self.convoy_intended => {
+                format!("{unit} - {} via Convoy", name(self.order_to))
+            }
+            Move => format!("{unit} - {}", name(self.order_to)),
+            Support if self.order_from == self.order_to => {
+                format!(
+                    "{unit} S {} {}",
+                    letter(supported_type),
+                    name(self.order_from)
+                )
+            }
+            Support => format!(
+                "{unit} S {} {} - {}",
+                letter(supported_type),
+                name(self.order_from),
+                name(self.order_to)
+            ),
+            // Only armies can be convoyed.
+            Convoy => format!(
+                "{unit} C A {} - {}",
+                name(self.order_from),
+                name(self.order_to)
+            ),
+            _ => format!("{unit} H"),
+        }
+    }
+
+    // A short description of how a resolved order turned out: "dislodged" if the unit was dislodged, and otherwise
+    // "bounced", "cut", "illegal", "void" (a support or convoy for an order that wasn't given), or "success".
+    pub fn outcome(&self) -> &'static str {
+        if self.dislodged {
+            return "dislodged";
+        }
+
+        match self.order_type {
+            Bounced => "bounced",
+            SupportCut => "cut",
+            IllegalOrder => "illegal",
+            RequiredOrderNotGiven => "void",
+            Hold | Move | Support | Convoy => "success",
+        }
+    }
+
     pub fn is_moving_into(&self, destination: ProvinceID) -> bool {
         (self.order_type == OrderType::Move) && (self.order_to == destination)
     }
@@ -85,9 +357,183 @@ impl Order {
         (self.order_type == OrderType::Convoy) && (self.order_from == from) && (self.order_to == to)
     }
 
+    pub fn original_order_type(&self) -> OrderType {
+        self.original_order_type
+    }
+
+    pub fn order_type(&self) -> OrderType {
+        self.order_type
+    }
+
+    pub fn set_order_type(&mut self, order_type: OrderType) {
+        self.order_type = order_type;
+    }
+
+    pub fn order_of(&self) -> ProvinceID {
+        self.order_of
+    }
+
+    pub fn order_from(&self) -> ProvinceID {
+        self.order_from
+    }
+
+    pub fn order_to(&self) -> ProvinceID {
+        self.order_to
+    }
+
+    pub fn convoy_intended(&self) -> bool {
+        self.convoy_intended
+    }
+
+    pub fn order_strength(&self) -> u8 {
+        self.order_strength
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.resolved
+    }
+
+    pub fn is_dislodged(&self) -> bool {
+        self.dislodged
+    }
+
+    // Puts the order back the way it was given, undoing everything a previous resolution changed, so that the same order
+    // can be resolved again without its supports being counted twice.
+    pub fn reset_for_resolution(&mut self) {
+        self.order_type = self.original_order_type;
+        self.order_strength = 1;
+        self.supported_by = ProvinceSet::default();
+        self.resolved = false;
+        self.dislodged = false;
+    }
+
     pub fn increase_strength(&mut self) {
         self.order_strength += 1;
     }
+
+    // The provinces whose supports actually contributed to this order's strength, once it has been resolved. Supports that
+    // were cut, or that didn't match what this unit was ordered to do, aren't included.
+    pub fn supporting_orders(&self) -> Vec<ProvinceID> {
+        self.supported_by.iter().collect()
+    }
+
+    // A quick check of whether the order makes sense on the board as it is, without resolving anything. On top of what the
+    // resolver treats as illegal, a convoying fleet needs to be part of a possible chain of fleets to convoy the army. Orders
+    // for provinces without a unit are never valid.
+    pub fn is_valid_for(&self, map: &Map) -> bool {
+        if map
+            .province(self.order_of)
+            .and_then(|province| province.occupied_by())
+            .is_none()
+            || !self.is_legal(map)
+        {
+            return false;
+        }
+
+        match self.order_type {
+            Convoy => map
+                .convoy_routes(self.order_from, self.order_to, &fleets_on(map))
+                .iter()
+                .any(|route| route.contains(&self.order_of)),
+            _ => true,
+        }
+    }
+
+    // Whether the order could ever be carried out on this map, regardless of what any other unit does. Orders for provinces
+    // without a unit are left for the resolver to ignore.
+    fn is_legal(&self, map: &Map) -> bool {
+        let Some(unit) = map
+            .province(self.order_of)
+            .and_then(|province| province.occupied_by())
+        else {
+            return true;
+        };
+        let unit_type = unit.get_type();
+
+        match self.order_type {
+            // Armies may be convoyed to provinces they aren't next to, as long as there are fleets that could convoy them
+            // there, but fleets can only move along their own connections. A move asked to go by convoy can't be made at all
+            // from a province that no convoy could start from (i.e. inland).
+            Move => {
+                let convoyable = map
+                    .province(self.order_of)
+                    .is_some_and(|origin| origin.province_type().can_convoy_out_of())
+                    && map.borders_sea(self.order_of);
+
+                map.province(self.order_to)
+                    .is_some_and(|destination| destination.province_type().can_hold(&unit_type))
+                    && (!self.convoy_intended || convoyable)
+                    && (map
+                        .neighbors(self.order_of, &unit_type)
+                        .contains(&self.order_to)
+                        || (unit_type == UnitType::Army
+                            && !map
+                                .convoy_routes(self.order_of, self.order_to, &fleets_on(map))
+                                .is_empty()))
+            }
+            // A unit can only support a move into (or a hold in) a province it could move to itself. For a fleet on a split
+            // coast, that means a province next to the coast it is actually on, though any coast of the target will do.
+            // A unit can never support a move into its own province, as that would be supporting its own dislodgement. Such a
+            // support is already ruled out by the first rule on any valid map, but is checked on its own in case a map
+            // connects a province to one of its own coasts.
+            Support => {
+                !map.is_same_province(self.order_to, self.order_of)
+                    && map
+                        .neighbors(self.order_of, &unit_type)
+                        .iter()
+                        .any(|neighbor| map.is_same_province(*neighbor, self.order_to))
+            }
+            // Only fleets in the sea can convoy (not fleets on a coast), and only armies can be convoyed.
+            Convoy => {
+                unit.can_convoy()
+                    && map
+                        .province(self.order_of)
+                        .is_some_and(|province| province.province_type().can_convoy_through())
+                    && map
+                        .occupant_including_coasts(self.order_from)
+                        .is_none_or(|(_, convoyed)| convoyed.can_be_convoyed())
+            }
+            _ => true,
+        }
+    }
+
+    // Counts the support given by the unit in `supporter` towards this order's strength.
+    fn add_support(&mut self, supporter: ProvinceID) {
+        self.increase_strength();
+        self.supported_by.insert(supporter);
+    }
+}
+
+/// All of the orders one player gave for a phase.
+#[derive(Clone, Debug)]
+pub struct OrderSet {
+    pub player: PlayerID,
+
+    pub orders: Vec<Order>,
+}
+
+impl OrderSet {
+    pub fn new(player: PlayerID, orders: Vec<Order>) -> Self {
+        Self { player, orders }
+    }
+}
+
+// A set of provinces, kept as a bit per possible `ProvinceID` so that `Order` can stay `Copy`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+struct ProvinceSet([u64; 4]);
+
+impl ProvinceSet {
+    fn insert(&mut self, province: ProvinceID) {
+        self.0[province as usize / 64] |= 1 << (province % 64);
+    }
+
+    fn contains(&self, province: ProvinceID) -> bool {
+        self.0[province as usize / 64] & (1 << (province % 64)) != 0
+    }
+
+    fn iter(&self) -> impl Iterator<Item = ProvinceID> + '_ {
+        (0..=ProvinceID::MAX).filter(|province| self.contains(*province))
+    }
 }
 
 pub fn create_order_dependency_graph<'a>(orders: &Vec<Order>) -> (OrderGraph, IndexMap<'a>) {
@@ -106,15 +552,28 @@ pub fn create_order_dependency_graph<'a>(orders: &Vec<Order>) -> (OrderGraph, In
     // 4. A unit moving into a location is dependant on any unit also moving into that location. (Causes a cycle)
     // 5. A unit moving into a location is dependant on any unit moving from its destination to the original units origin (Causes a cycle)
     // 6. A unit moving into a location is dependant on any unit that is convoying it. This can lead to a convoy paradox.
-    for (current_order_idx, current_order) in &nodes {
-        for (check_order_idx, check_order) in &nodes {
+    // Go through the orders in the order they were given rather than the `HashMap`'s order, so that the edges (and so the
+    // cycle that is broken first, see `resolve_all_non_dependant_edges`) are the same every time.
+    let mut indexed_orders: Vec<(NodeIndex, Order)> = nodes
+        .iter()
+        .map(|(index, order)| (*index, *order))
+        .collect();
+    indexed_orders.sort_by_key(|(index, _)| *index);
+
+    for (current_order_idx, current_order) in &indexed_orders {
+        for (check_order_idx, check_order) in &indexed_orders {
+            // An order is never dependant on itself.
+            if current_order_idx == check_order_idx {
+                continue;
+            }
+
             // Helper function to reduce duplicate edge adding code.
             let mut add_edge = || {
                 ret_graph.add_edge(*current_order_idx, *check_order_idx, ());
             };
 
             match current_order.order_type {
-                Hold | Convoy | Support | RequiredOrderNotGiven | SupportCut => {
+                Hold | Convoy | Support | RequiredOrderNotGiven | SupportCut | Bounced => {
                     // (1) If we are Holding, then we are only dependant on moves that support hold us.
                     if check_order.is_support_holding(current_order.order_of) ||
                     // (2) If we are holding, then any unit moving into our province may dislodge us or cut support.
@@ -153,46 +612,2304 @@ pub fn create_order_dependency_graph<'a>(orders: &Vec<Order>) -> (OrderGraph, In
     (ret_graph, nodes)
 }
 
-pub fn resolve_all_non_dependant_edges<'a>(order_graph: &mut OrderGraph, nodes: &mut IndexMap) {
+// Resolves the orders in `nodes` exactly as they are given. Orders that can never succeed are only marked as such before
+// the graph is built (see `resolve_orders`), so that the graph's dependencies already reflect them.
+pub fn resolve_all_non_dependant_edges(
+    order_graph: &mut OrderGraph,
+    nodes: &mut IndexMap,
+    map: &Map,
+    paradox_rule: ParadoxRule,
+) -> Result<ResolutionStats, AdjudicationError> {
+    resolve_graph(order_graph, nodes, map, paradox_rule, false).map(|(stats, _)| stats)
+}
+
+// The same as `resolve_all_non_dependant_edges`, but also returns every decision made, in order, if `tracing` is set.
+fn resolve_graph(
+    order_graph: &mut OrderGraph,
+    nodes: &mut IndexMap,
+    map: &Map,
+    paradox_rule: ParadoxRule,
+    tracing: bool,
+) -> Result<(ResolutionStats, Vec<ResolutionStep>), AdjudicationError> {
+    // A node without an order can never be resolved, and nothing depending on it could be either.
+    let missing: Vec<usize> = order_graph
+        .node_indices()
+        .filter(|index| !nodes.contains_key(index))
+        .map(|index| index.index())
+        .collect();
+    if !missing.is_empty() {
+        return Err(AdjudicationError::Deadlock(missing));
+    }
+
+    let mut resolution = Resolution::new(nodes, map, paradox_rule, tracing);
+    let mut stats = ResolutionStats::default();
+
     // Whether or not any orders have been resolved this iteration. Starts as true so that we enter the while loop the first time.
     let mut any_resolved = true;
 
     while any_resolved {
         any_resolved = false;
+        stats.iterations += 1;
 
         for index in order_graph.node_indices() {
-            if (!nodes.get(&index).unwrap().resolved)
+            if (!resolution.is_resolved(index.index()))
                 && order_graph.edges_directed(index, Outgoing).count() == 0
             {
                 // This means that we have an unresolved order with no dependencies; it is ready to be resolved!
-                let current_order = nodes.get(&index).unwrap();
+                resolution.resolve(index.index());
 
-                match current_order.order_type {
-                    Support => {
-                        // Find the unit that we are supporting, and increase its strength by one, then remove its dependency on this order.
-                        let mut incoming_edges = order_graph.edges_directed(index, Incoming);
+                // Update any_resolved so that we go through another time (as this order being resolved may leave other order with no dependencies)
+                any_resolved = true;
+            }
+        }
 
-                        // There should only ever be exactly one edge dependant on this one.
-                        let incoming_edge = incoming_edges.next().unwrap();
-                        assert!(incoming_edges.next().is_none());
+        if !any_resolved {
+            // Every remaining order is dependant on another unresolved order, so they must be part of (or waiting on) a cycle.
+            // Resolving any order in a cycle breaks it, so just take the first order of the first cycle we find.
+            // `visit_cycles` doesn't find cycles of only two orders, so if it finds nothing, follow the dependencies of the
+            // first unresolved order until they loop back on themselves instead.
+            let cycle = order_graph
+                .visit_cycles(|_graph, cycle| ControlFlow::Break(cycle.to_vec()))
+                .or_else(|| {
+                    order_graph
+                        .node_indices()
+                        .find(|index| !resolution.is_resolved(index.index()))
+                        .map(|index| find_cycle(order_graph, index))
+                });
 
-                        // Grab the supported unit, and increase its strength by 1.
-                        nodes
-                            .get_mut(&incoming_edge.source())
-                            .unwrap()
-                            .increase_strength();
+            if let Some(index) = cycle.as_ref().and_then(|cycle| cycle.first().copied()) {
+                // `Resolution` guesses at the first order and backs out of any guess that turns out to be inconsistent. A
+                // cycle of decisions with no single consistent result is settled by the backup rule for its kind (see
+                // `Resolution::apply_backup_rule`).
+                stats.cycles_detected += 1;
+                resolution.resolve(index.index());
 
-                        // Now remove that dependency from the graph
-                        order_graph.remove_edge(incoming_edge.id());
-                    }
-                    _ => {
-                        unimplemented!();
-                    }
+                // Resolving an order in a cycle should always resolve it. If it didn't, we'd pick the same order again on the
+                // next pass and never finish, so give up instead.
+                if !resolution.is_resolved(index.index()) {
+                    let unresolved = order_graph
+                        .node_indices()
+                        .map(|index| index.index())
+                        .filter(|index| !resolution.is_resolved(*index))
+                        .collect();
+                    return Err(AdjudicationError::Deadlock(unresolved));
                 }
 
-                // Update any_resolved so that we go through another time (as this order being resolved may leave other order with no dependencies)
                 any_resolved = true;
             }
         }
+
+        // Resolving one order can resolve many others along with it, so remove the dependencies on all of them.
+        for index in order_graph.node_indices() {
+            if resolution.is_resolved(index.index()) {
+                let incoming_edges: Vec<_> = order_graph
+                    .edges_directed(index, Incoming)
+                    .map(|edge| edge.id())
+                    .collect();
+                for edge in incoming_edges {
+                    order_graph.remove_edge(edge);
+                }
+            }
+        }
+    }
+
+    stats.cycle_kinds = std::mem::take(&mut resolution.cycle_kinds);
+    let trace = resolution.trace.take().unwrap_or_default();
+    for (index, order) in resolution.finish().into_iter().enumerate() {
+        nodes.insert(NodeIndex::new(index), order);
+        stats.orders_resolved += 1;
+    }
+
+    Ok((stats, trace))
+}
+
+// The orders in a cycle, starting from `start`, found by following the first dependency of each order until an order is
+// reached a second time. Every unresolved order is dependant on another, so this always ends up going round a cycle, though
+// `start` itself may only lead into it.
+fn find_cycle(order_graph: &OrderGraph, start: NodeIndex) -> Vec<NodeIndex> {
+    let mut path = vec![start];
+    let mut current = start;
+
+    while let Some(next) = order_graph.neighbors_directed(current, Outgoing).next() {
+        if let Some(position) = path.iter().position(|index| *index == next) {
+            return path.split_off(position);
+        }
+        path.push(next);
+        current = next;
+    }
+
+    path
+}
+
+// What kind of cycle of decisions had either no consistent result or more than one while resolving, which decides how it
+// is settled (see `Resolution::apply_backup_rule`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CycleKind {
+    // Two units moving into each other's provinces, at least one of them by convoy. Both moves succeed.
+    Swap,
+
+    // Three or more units moving in a circle, each into the province of the next. Every move succeeds.
+    Rotation,
+
+    // A cycle that includes whether a convoy arrives, where whether the convoy succeeds depends on the move it is convoying
+    // (i.e. by cutting a support for an attack on the fleet). Settled by the `ParadoxRule`.
+    ConvoyParadox,
+
+    // Any other cycle. Every move in it fails.
+    Other,
+}
+
+// A single decision made while resolving orders, for following how an adjudication was reached (see
+// `resolve_orders_traced`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolutionStep {
+    // The support given by the unit in the province counts.
+    SupportGiven(ProvinceID),
+
+    // The support given by the unit in the province was cut, or was never needed (i.e. a support for a move that bounces
+    // anyway doesn't need to be decided).
+    SupportCut(ProvinceID),
+
+    MoveSucceeded { from: ProvinceID, to: ProvinceID },
+
+    MoveBounced { from: ProvinceID, to: ProvinceID },
+
+    // The army in the province did, or didn't, make it along a convoy route.
+    ConvoyArrived(ProvinceID),
+    ConvoyFailed(ProvinceID),
+
+    // Every remaining order was part of (or waiting on) a cycle of this kind, so the cycle had to be broken.
+    CycleResolved(CycleKind),
+}
+
+// How much work `resolve_all_non_dependant_edges` had to do, for profiling slow adjudications.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResolutionStats {
+    // The number of passes made over the dependency graph.
+    pub iterations: usize,
+
+    // The number of times every remaining order was part of (or waiting on) a cycle, so one had to be resolved to break it.
+    pub cycles_detected: usize,
+
+    // The number of orders that were resolved.
+    pub orders_resolved: usize,
+
+    // The kind of each cycle that was broken, in the order they were broken.
+    pub cycle_kinds: Vec<CycleKind>,
+}
+
+// Resolves a full set of orders for a single movement phase, returning them in the same order they were given.
+// Orders that were already resolved are reset first, so they are resolved from scratch.
+pub fn resolve_orders(
+    orders: &[Order],
+    map: &Map,
+    paradox_rule: ParadoxRule,
+) -> Result<Vec<Order>, AdjudicationError> {
+    resolve_orders_with_stats(orders, map, paradox_rule).map(|(orders, _)| orders)
+}
+
+// The same as `resolve_orders`, but also returns how much work the resolution took (see `ResolutionStats`).
+pub fn resolve_orders_with_stats(
+    orders: &[Order],
+    map: &Map,
+    paradox_rule: ParadoxRule,
+) -> Result<(Vec<Order>, ResolutionStats), AdjudicationError> {
+    resolve_orders_with(orders, map, paradox_rule, false, false)
+        .map(|(orders, stats, _)| (orders, stats))
+}
+
+// The same as `resolve_orders_with_stats`, but also returns every decision made along the way, in the order they were made
+// (see `ResolutionStep`). Keeping the trace is slower, so only use this when the steps are wanted.
+pub fn resolve_orders_traced(
+    orders: &[Order],
+    map: &Map,
+    paradox_rule: ParadoxRule,
+) -> Result<(Vec<Order>, ResolutionStats, Vec<ResolutionStep>), AdjudicationError> {
+    resolve_orders_with(orders, map, paradox_rule, false, true)
+}
+
+// Resolves the orders using every rule of the variant that affects resolution (the paradox rule, and whether illegal orders
+// become holds), returning the same as `resolve_orders_traced`. The trace is left empty unless `tracing` is set.
+pub fn resolve_orders_for_variant(
+    orders: &[Order],
+    map: &Map,
+    config: &VariantConfig,
+    tracing: bool,
+) -> Result<(Vec<Order>, ResolutionStats, Vec<ResolutionStep>), AdjudicationError> {
+    resolve_orders_with(
+        orders,
+        map,
+        config.paradox_rule(),
+        config.illegal_orders_become_holds(),
+        tracing,
+    )
+}
+
+fn resolve_orders_with(
+    orders: &[Order],
+    map: &Map,
+    paradox_rule: ParadoxRule,
+    illegal_as_holds: bool,
+    tracing: bool,
+) -> Result<(Vec<Order>, ResolutionStats, Vec<ResolutionStep>), AdjudicationError> {
+    map.validate()?;
+    let mut orders = orders.to_vec();
+    for order in &mut orders {
+        order.reset_for_resolution();
+    }
+    mark_illegal_orders(&mut orders, map, illegal_as_holds);
+
+    let (mut order_graph, mut nodes) = create_order_dependency_graph(&orders);
+    let (stats, trace) = resolve_graph(&mut order_graph, &mut nodes, map, paradox_rule, tracing)?;
+
+    let orders = (0..orders.len())
+        .map(|index| nodes[&NodeIndex::new(index)])
+        .collect();
+    Ok((orders, stats, trace))
+}
+
+// The unit in `province`, or if there isn't one, a unit of the type the order said would be there.
+fn unit_in(map: &Map, province: ProvinceID, written_type: UnitType) -> Unit {
+    map.occupant_including_coasts(province)
+        .map(|(_, unit)| unit.clone())
+        .unwrap_or_else(|| Unit::new(NEUTRAL, written_type))
+}
+
+// Every fleet on the map, wherever it is. `Map::convoy_routes` only uses the ones that can convoy.
+fn fleets_on(map: &Map) -> Vec<ProvinceID> {
+    map.occupied()
+        .filter(|(_, unit)| unit.get_type() == UnitType::Fleet)
+        .map(|(id, _)| id)
+        .collect()
+}
+
+// Sorts orders by the province of the unit they were given to, so that they are always listed in the same order no matter
+// how they were collected (i.e. from several players' `OrderSet`s).
+pub fn sort_orders_by_province(orders: &mut [Order]) {
+    orders.sort_by_key(|order| order.order_of);
+}
+
+// The order given to the unit in `province`, if there is one. Only an order given to exactly that province matches, so an
+// order for a fleet on a coast isn't found from its parent.
+pub fn order_for(orders: &[Order], province: ProvinceID) -> Option<&Order> {
+    orders.iter().find(|order| order.order_of == province)
+}
+
+// The same as `order_for`, but allows the order to be changed.
+pub fn order_for_mut(orders: &mut [Order], province: ProvinceID) -> Option<&mut Order> {
+    orders.iter_mut().find(|order| order.order_of == province)
+}
+
+// The fleets ordered to convoy the army in `from` to `to`. Only these fleets may make up a convoy route for that move (see
+// `Map::convoy_routes`); any other fleet along the way, even one convoying a different army, doesn't help.
+pub fn convoying_fleets(orders: &[Order], from: ProvinceID, to: ProvinceID) -> Vec<ProvinceID> {
+    orders
+        .iter()
+        .filter(|order| order.is_convoying(from, to))
+        .map(|order| order.order_of)
+        .collect()
+}
+
+// Updates the type of every order that can never succeed, before anything is resolved. The original order type is kept.
+// A unit ordered to move to the province it is already in (i.e. "A Par - Par") is treated as holding, and orders that could
+// never be carried out are marked as illegal (see `Order::is_legal`), or as holding if `illegal_as_holds` is set.
+fn mark_illegal_orders(orders: &mut [Order], map: &Map, illegal_as_holds: bool) {
+    for order in orders {
+        if order.order_type == Move && map.is_same_province(order.order_of, order.order_to) {
+            order.order_type = Hold;
+        } else if !order.is_legal(map) {
+            order.order_type = if illegal_as_holds { Hold } else { IllegalOrder };
+        }
+    }
+}
+
+// Whether an attack of strength `attack` beats a defense of strength `defense`, either dislodging the defender or (for a
+// defense of 0) entering the empty province. Ties always go to the defender.
+pub fn dislodges(attack: u8, defense: u8) -> bool {
+    attack > defense
+}
+
+// Whether a move of strength `best` gets into a province that the moves of strength `others` are also trying to enter.
+// The move must be strictly stronger than every other one; if two moves tie for strongest, they all bounce.
+pub fn wins_contest(best: u8, others: &[u8]) -> bool {
+    others.iter().all(|other| best > *other)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DecisionState {
+    Unresolved,
+    Guessing,
+    Resolved,
+}
+
+// Working state used while resolving a turn. Orders are indexed by their node index in the order graph.
+//
+// Every `Move` has a decision of whether or not it succeeds, and every `Support` has a decision of whether or not it is given.
+// Every `Move` also has a decision of whether or not it arrives by convoy, which only matters for convoyed armies.
+// Path decisions come after the order decisions, so the path decision for the order at `index` is at `orders.len() + index`.
+// Decisions are made recursively, each one resolving the decisions it relies on. When a decision ends up relying on itself,
+// we guess its result, and check whether the guess holds up (see `resolve`).
+struct Resolution<'a> {
+    map: &'a Map,
+
+    orders: Vec<Order>,
+
+    // The owner of the unit receiving each order. None if there is no unit in the ordered province.
+    owners: Vec<Option<PlayerID>>,
+
+    // Whether each order can reach its destination without being convoyed. Only armies moving to a province they aren't
+    // next to can't.
+    by_land: Vec<bool>,
+
+    // Whether each order is an army that is trying to be convoyed.
+    //
+    // An army that can't reach its destination by land always tries to be convoyed. An army that can is only convoyed if the
+    // player asked for it to be (see `Order::convoy_intended`), or if one of its own player's fleets was ordered to convoy it
+    // there; otherwise any convoys ordered for it are ignored, and it moves over land. If the convoy of an army with a land
+    // route is disrupted, it still moves over land, unless the player explicitly asked for the convoy (see `has_path`).
+    convoyed: Vec<bool>,
+
+    paradox_rule: ParadoxRule,
+
+    // Convoy routes already found, as each convoy's path is decided again after every guess. The map and the orders never
+    // change during a resolution, so nothing here ever goes stale.
+    convoy_planner: ConvoyPlanner,
+
+    states: Vec<DecisionState>,
+
+    results: Vec<bool>,
+
+    // Orders whose current result is based on a guess, in the order they were found. An order is listed again each time
+    // another decision relies on it.
+    guess_dependencies: Vec<usize>,
+
+    // Every decision made so far, in order. None unless a trace was asked for, so that the usual resolution doesn't have to
+    // keep one.
+    trace: Option<Vec<ResolutionStep>>,
+
+    // The kind of every cycle settled by `apply_backup_rule`, in the order they were settled.
+    cycle_kinds: Vec<CycleKind>,
+}
+
+impl<'a> Resolution<'a> {
+    fn new(nodes: &IndexMap, map: &'a Map, paradox_rule: ParadoxRule, tracing: bool) -> Self {
+        let mut indexed_orders: Vec<(&NodeIndex, &Order)> = nodes.iter().collect();
+        indexed_orders.sort_by_key(|(index, _)| index.index());
+        let orders: Vec<Order> = indexed_orders
+            .into_iter()
+            .map(|(_, order)| *order)
+            .collect();
+
+        let owners: Vec<Option<PlayerID>> = orders
+            .iter()
+            .map(|order| {
+                map.province(order.order_of)
+                    .and_then(|province| province.occupied_by())
+                    .map(|unit| unit.owner())
+            })
+            .collect();
+
+        let is_army_move = |order: &Order| {
+            order.order_type == Move
+                && map
+                    .province(order.order_of)
+                    .and_then(|province| province.occupied_by())
+                    .is_some_and(|unit| unit.get_type() == UnitType::Army)
+        };
+
+        let by_land: Vec<bool> = orders
+            .iter()
+            .map(|order| {
+                !is_army_move(order)
+                    || map
+                        .neighbors(order.order_of, &UnitType::Army)
+                        .contains(&order.order_to)
+            })
+            .collect();
+
+        let convoyed = orders
+            .iter()
+            .enumerate()
+            .map(|(index, order)| {
+                let convoyed_by_own_fleet =
+                    orders.iter().enumerate().any(|(convoy_index, convoy)| {
+                        convoy.order_type == Convoy
+                            && map.is_same_province(convoy.order_from, order.order_of)
+                            && map.is_same_province(convoy.order_to, order.order_to)
+                            && owners[convoy_index].is_some()
+                            && owners[convoy_index] == owners[index]
+                    });
+
+                is_army_move(order)
+                    && (!by_land[index] || order.convoy_intended || convoyed_by_own_fleet)
+            })
+            .collect();
+
+        let mut resolution = Self {
+            map,
+            owners,
+            by_land,
+            convoyed,
+            paradox_rule,
+            convoy_planner: ConvoyPlanner::new(),
+            states: vec![DecisionState::Unresolved; orders.len() * 2],
+            results: vec![false; orders.len() * 2],
+            orders,
+            guess_dependencies: Vec::new(),
+            trace: tracing.then(Vec::new),
+            cycle_kinds: Vec::new(),
+        };
+        resolution.mark_unmatched_supports();
+        resolution.mark_unmatched_convoys();
+        resolution
+    }
+
+    // A support is only valid if the supported unit was actually ordered to do what it is being supported to do.
+    fn mark_unmatched_supports(&mut self) {
+        for index in 0..self.orders.len() {
+            if self.orders[index].order_type == Support && self.supported_order(index).is_none() {
+                self.orders[index].order_type = RequiredOrderNotGiven;
+            }
+        }
+    }
+
+    // A convoy is only valid if the convoyed army was actually ordered to make that move, and is trying to be convoyed
+    // (see `convoyed`). So a convoy for an army that was ordered straight to a province it could reach over land isn't
+    // needed, and isn't given. The exception is a convoy by one of the army's own player's fleets: that player is taken to
+    // have meant the army to go by convoy, even without writing "via Convoy" (as DATC 6.G expects), so the convoy stands.
+    fn mark_unmatched_convoys(&mut self) {
+        for index in 0..self.orders.len() {
+            let convoy = self.orders[index];
+            if convoy.order_type != Convoy {
+                continue;
+            }
+
+            let matched = self.unit_at(convoy.order_from).is_some_and(|army| {
+                self.orders[army].order_type == Move
+                    && self.convoyed[army]
+                    && self.same_province(self.orders[army].order_to, convoy.order_to)
+            });
+            if !matched {
+                self.orders[index].order_type = RequiredOrderNotGiven;
+            }
+        }
+    }
+
+    // Whether or not the order has nothing left to decide.
+    fn is_resolved(&self, index: usize) -> bool {
+        match self.orders[index].order_type {
+            Move | Support => self.states[index] == DecisionState::Resolved,
+            _ => true,
+        }
+    }
+
+    // Coasts are part of their parent province when it comes to occupying and attacking (i.e. Spain North Coast is in Spain).
+    fn same_province(&self, province_1: ProvinceID, province_2: ProvinceID) -> bool {
+        self.map.is_same_province(province_1, province_2)
+    }
+
+    fn same_owner(&self, index_1: usize, index_2: usize) -> bool {
+        self.owners[index_1].is_some() && self.owners[index_1] == self.owners[index_2]
+    }
+
+    // The order given to the unit in `province`, if any.
+    fn unit_at(&self, province: ProvinceID) -> Option<usize> {
+        (0..self.orders.len())
+            .find(|index| self.same_province(self.orders[*index].order_of, province))
+    }
+
+    fn moves_into(&self, province: ProvinceID) -> Vec<usize> {
+        (0..self.orders.len())
+            .filter(|index| {
+                self.orders[*index].order_type == Move
+                    && self.same_province(self.orders[*index].order_to, province)
+            })
+            .collect()
+    }
+
+    // The unit moving directly into this move's origin from its destination, if any. Units that are convoyed pass each other
+    // by, so they never take part in a head to head battle.
+    fn head_to_head_opponent(&mut self, index: usize) -> Option<usize> {
+        let order = self.orders[index];
+        if order.order_type != Move {
+            return None;
+        }
+
+        let opponent = self.unit_at(order.order_to)?;
+        let opponent_order = self.orders[opponent];
+        if opponent_order.order_type != Move
+            || !self.same_province(opponent_order.order_to, order.order_of)
+        {
+            return None;
+        }
+
+        if self.arrives_by_convoy(index) || self.arrives_by_convoy(opponent) {
+            return None;
+        }
+
+        Some(opponent)
+    }
+
+    // Whether the move at `index` can reach its destination at all, either over land or by convoy.
+    fn has_path(&mut self, index: usize) -> bool {
+        (self.by_land[index] && !self.orders[index].convoy_intended)
+            || self.arrives_by_convoy(index)
+    }
+
+    fn arrives_by_convoy(&mut self, index: usize) -> bool {
+        self.convoyed[index] && self.resolve(self.orders.len() + index)
+    }
+
+    // The order that the support at `index` is supporting. None if no unit was given a matching order.
+    fn supported_order(&self, index: usize) -> Option<usize> {
+        let support = self.orders[index];
+        let supported = self.unit_at(support.order_from)?;
+        let supported_order = self.orders[supported];
+
+        let matches = if self.same_province(support.order_from, support.order_to) {
+            // Support holding: any order that keeps the unit where it is, apart from illegal ones. A unit ordered to move to
+            // its own province only holds because the move can't be made, so a support for that move doesn't become a
+            // support hold.
+            !matches!(supported_order.order_type, Move | IllegalOrder)
+                && (supported_order.original_order_type != Move
+                    || !self.same_province(supported_order.order_of, supported_order.order_to))
+        } else {
+            supported_order.order_type == Move
+                && self.same_province(supported_order.order_to, support.order_to)
+        };
+
+        (matches && supported != index).then_some(supported)
+    }
+
+    fn supports_for(&self, index: usize) -> Vec<usize> {
+        (0..self.orders.len())
+            .filter(|support| {
+                self.orders[*support].order_type == Support
+                    && self.supported_order(*support) == Some(index)
+            })
+            .collect()
+    }
+
+    // The supports actually given to the order at `index`, not counting any from the player who owns the unit given the
+    // order at `ignoring_owner_of`.
+    fn counted_supports(&mut self, index: usize, ignoring_owner_of: Option<usize>) -> Vec<usize> {
+        let mut counted = Vec::new();
+        for support in self.supports_for(index) {
+            if ignoring_owner_of.is_some_and(|ignored| self.same_owner(support, ignored)) {
+                continue;
+            }
+            if self.resolve(support) {
+                counted.push(support);
+            }
+        }
+        counted
+    }
+
+    // The number of supports in `counted_supports`.
+    fn given_supports(&mut self, index: usize, ignoring_owner_of: Option<usize>) -> u8 {
+        self.counted_supports(index, ignoring_owner_of).len() as u8
+    }
+
+    // How hard the unit in `province` is holding on to it.
+    fn hold_strength(&mut self, province: ProvinceID) -> u8 {
+        match self.unit_at(province) {
+            // A unit that wasn't given an order still holds its province, it just can't be supported. Give it a `Hold` order
+            // (see `GameState::fill_missing_with_holds`) if it needs to be.
+            None if self.map.is_province_occupied_including_coasts(province) => 1,
+            None => 0,
+            // A unit that successfully moves away leaves its province empty, so a unit following it in (a "train" of moves)
+            // only has to beat any other unit moving there. If it fails to leave, it holds as normal, and the follower bounces.
+            Some(index) if self.orders[index].order_type == Move => {
+                if self.resolve(index) {
+                    0
+                } else {
+                    1
+                }
+            }
+            // Any other unit stays where it is, and every support hold it is given (and that isn't cut) adds to its strength.
+            Some(index) => 1 + self.given_supports(index, None),
+        }
+    }
+
+    fn attack_strength(&mut self, index: usize) -> u8 {
+        if !self.has_path(index) {
+            return 0;
+        }
+
+        if let Some(defender) = self.staying_defender(index) {
+            // The defender is staying where it is. A unit can never dislodge a unit of its own player, and supports from
+            // the defending player never count towards dislodging it. So a support hold from the same player only ever
+            // matters against foreign attackers, as a friendly one has no attack strength to beat.
+            return if self.same_owner(index, defender) {
+                0
+            } else {
+                1 + self.given_supports(index, Some(defender))
+            };
+        }
+
+        match self.unit_at(self.orders[index].order_to) {
+            None => match self
+                .map
+                .occupant_including_coasts(self.orders[index].order_to)
+            {
+                // The same goes for units that weren't given an order.
+                Some((_, unit)) if self.owners[index] == Some(unit.owner()) => 0,
+                _ => 1 + self.given_supports(index, None),
+            },
+            Some(_) => 1 + self.given_supports(index, None),
+        }
+    }
+
+    // The unit that the move at `index` has to dislodge to succeed, if there is one: a unit in its destination that isn't
+    // moving, is fighting it head to head, or fails to leave. Supports from that unit's player never count for the move.
+    fn staying_defender(&mut self, index: usize) -> Option<usize> {
+        let defender = self.unit_at(self.orders[index].order_to)?;
+        (self.orders[defender].order_type != Move
+            || self.head_to_head_opponent(index) == Some(defender)
+            || !self.resolve(defender))
+        .then_some(defender)
+    }
+
+    // How strongly a unit in a head to head battle is pushing back.
+    fn defend_strength(&mut self, index: usize) -> u8 {
+        1 + self.given_supports(index, None)
+    }
+
+    // How strongly a unit moving into the same province keeps others out of it.
+    fn prevent_strength(&mut self, index: usize) -> u8 {
+        if !self.has_path(index) {
+            return 0;
+        }
+
+        if let Some(opponent) = self.head_to_head_opponent(index)
+            && self.resolve(opponent)
+        {
+            // A unit that lost a head to head battle can't keep anyone else out.
+            return 0;
+        }
+
+        1 + self.given_supports(index, None)
+    }
+
+    fn adjudicate(&mut self, index: usize) -> bool {
+        if index >= self.orders.len() {
+            return self.adjudicate_path(index - self.orders.len());
+        }
+
+        let order = self.orders[index];
+
+        match order.order_type {
+            // There is no tie-break anywhere in a move: it only succeeds if it is strictly stronger than both the defense and
+            // every other move into the province, so equal strengths always bounce whatever order the orders were given in.
+            Move => {
+                let attack = self.attack_strength(index);
+
+                let defense = match self.head_to_head_opponent(index) {
+                    Some(opponent) => self.defend_strength(opponent),
+                    None => self.hold_strength(order.order_to),
+                };
+                if !dislodges(attack, defense) {
+                    return false;
+                }
+
+                let competitors: Vec<u8> = self
+                    .moves_into(order.order_to)
+                    .into_iter()
+                    .filter(|competitor| *competitor != index)
+                    .map(|competitor| self.prevent_strength(competitor))
+                    .collect();
+
+                wins_contest(attack, &competitors)
+            }
+            Support => {
+                // Support is cut by any attack, except one coming from the province the support is directed at. The attack only
+                // has to be made, so a move that bounces still cuts the support. A player can't cut their own support, and an
+                // army whose convoy failed never arrives to cut it.
+                let cut = self.moves_into(order.order_of).into_iter().any(|attacker| {
+                    !self.same_province(self.orders[attacker].order_of, order.order_to)
+                        && !self.same_owner(attacker, index)
+                        && self.has_path(attacker)
+                });
+
+                // A dislodged unit gives no support at all, even when the attack that dislodged it came from the province the
+                // support was directed at. When the unit being attacked is the one attacking the supporter, whether the supporter
+                // is dislodged and whether the supported attack succeeds depend on each other. That is a cycle like any other,
+                // and is guessed through in `resolve` (see DATC 6.D.15 and 6.D.17).
+                !cut && !self.is_dislodged(index)
+            }
+            _ => true,
+        }
+    }
+
+    // A convoyed army arrives by convoy if every fleet along at least one convoy route stays where it is. When the fleets
+    // make up several routes (see `Map::convoy_routes`), each is checked separately, so a dislodged fleet only breaks the
+    // routes it is part of.
+    // Unlike a support, a convoy is only disrupted by the fleet being dislodged; being attacked isn't enough. This includes a
+    // fleet attacked by several equally strong moves that all bounce (a beleaguered garrison), which keeps its routes open.
+    fn adjudicate_path(&mut self, index: usize) -> bool {
+        if !self.convoyed[index] {
+            return false;
+        }
+
+        let order = self.orders[index];
+        let convoying_fleets = convoying_fleets(&self.orders, order.order_of, order.order_to);
+        let routes = self
+            .convoy_planner
+            .convoy_routes(self.map, order.order_of, order.order_to, &convoying_fleets)
+            .to_vec();
+
+        routes.iter().any(|route| {
+            route.iter().all(|fleet| match self.unit_at(*fleet) {
+                Some(convoy) => !self.is_dislodged(convoy),
+                None => false,
+            })
+        })
+    }
+
+    // Resolves the decision for the order at `index`, along with any decisions it relies on.
+    //
+    // If the decision ends up relying on itself, we first guess that it fails, and then that it succeeds. If exactly one of
+    // those guesses is consistent with the result it produces, that is the answer. Otherwise the orders involved are
+    // ambiguous, and are settled with `apply_backup_rule`.
+    fn resolve(&mut self, index: usize) -> bool {
+        match self.states[index] {
+            DecisionState::Resolved => return self.results[index],
+            DecisionState::Guessing => {
+                // We've looped back around to a decision we're guessing at, so anything asking for it is relying on that guess.
+                // It is added every time, even if it is already listed, as that is how the decision asking for it knows it
+                // relied on a guess (see below).
+                self.guess_dependencies.push(index);
+                return self.results[index];
+            }
+            DecisionState::Unresolved => {}
+        }
+
+        let has_decision = match index.checked_sub(self.orders.len()) {
+            Some(path) => self.orders[path].order_type == Move,
+            None => matches!(self.orders[index].order_type, Move | Support),
+        };
+        if !has_decision {
+            return true;
+        }
+
+        let old_count = self.guess_dependencies.len();
+
+        self.results[index] = false;
+        self.states[index] = DecisionState::Guessing;
+        let first_result = self.adjudicate(index);
+
+        if self.guess_dependencies.len() == old_count {
+            // Nothing relied on a guess, so this result is final.
+            if self.states[index] != DecisionState::Resolved {
+                self.settle(index, first_result);
+            }
+            return first_result;
+        }
+
+        if self.guess_dependencies[old_count] != index {
+            // This decision is part of a cycle that was started by a guess further up; that decision will sort it out.
+            self.guess_dependencies.push(index);
+            self.results[index] = first_result;
+            return first_result;
+        }
+
+        // This decision started the cycle. Forget everything that relied on the first guess, and try the other one.
+        self.reset_guess_dependencies(old_count);
+        self.results[index] = true;
+        self.states[index] = DecisionState::Guessing;
+        let second_result = self.adjudicate(index);
+
+        if first_result == second_result {
+            // Only one of the guesses was consistent, so we have our answer.
+            self.reset_guess_dependencies(old_count);
+            self.settle(index, first_result);
+            return first_result;
+        }
+
+        self.apply_backup_rule(old_count);
+        self.resolve(index)
+    }
+
+    // Makes `result` the final answer for the decision at `index`, adding it to the trace if there is one.
+    fn settle(&mut self, index: usize, result: bool) {
+        self.results[index] = result;
+        self.states[index] = DecisionState::Resolved;
+
+        if self.trace.is_none() {
+            return;
+        }
+        let step = match index.checked_sub(self.orders.len()) {
+            Some(army) if result => ResolutionStep::ConvoyArrived(self.orders[army].order_of),
+            Some(army) => ResolutionStep::ConvoyFailed(self.orders[army].order_of),
+            None => {
+                let order = self.orders[index];
+                match (order.order_type, result) {
+                    (Support, true) => ResolutionStep::SupportGiven(order.order_of),
+                    (Support, false) => ResolutionStep::SupportCut(order.order_of),
+                    (_, true) => ResolutionStep::MoveSucceeded {
+                        from: order.order_of,
+                        to: order.order_to,
+                    },
+                    (_, false) => ResolutionStep::MoveBounced {
+                        from: order.order_of,
+                        to: order.order_to,
+                    },
+                }
+            }
+        };
+        self.record(step);
+    }
+
+    fn record(&mut self, step: ResolutionStep) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(step);
+        }
+    }
+
+    fn reset_guess_dependencies(&mut self, old_count: usize) {
+        for dependency in self.guess_dependencies.drain(old_count..) {
+            self.states[dependency] = DecisionState::Unresolved;
+        }
+    }
+
+    // Works out what kind of cycle the decisions at `cycle` make. Any decision of whether a convoy arrives makes it a convoy
+    // paradox. Otherwise, it is circular movement if every decision in it is a move into the province of another unit
+    // moving in the cycle.
+    fn classify_cycle(&self, cycle: &[usize]) -> CycleKind {
+        let order_count = self.orders.len();
+        if cycle.iter().any(|index| *index >= order_count) {
+            return CycleKind::ConvoyParadox;
+        }
+
+        // A decision is listed once for each time another decision relied on it.
+        let mut decisions = cycle.to_vec();
+        decisions.sort_unstable();
+        decisions.dedup();
+
+        let circular = decisions.iter().all(|index| {
+            let order = self.orders[*index];
+            order.order_type == Move
+                && decisions
+                    .iter()
+                    .any(|other| self.same_province(self.orders[*other].order_of, order.order_to))
+        });
+        match decisions.len() {
+            2 if circular => CycleKind::Swap,
+            len if len >= 3 && circular => CycleKind::Rotation,
+            _ => CycleKind::Other,
+        }
+    }
+
+    // Settles a cycle of decisions that has either no consistent result, or more than one, using the rule for its kind
+    // (see `CycleKind`).
+    fn apply_backup_rule(&mut self, old_count: usize) {
+        let cycle: Vec<usize> = self.guess_dependencies.drain(old_count..).collect();
+        let kind = self.classify_cycle(&cycle);
+        self.cycle_kinds.push(kind);
+        self.record(ResolutionStep::CycleResolved(kind));
+
+        let order_count = self.orders.len();
+        let is_path = |index: &usize| *index >= order_count;
+
+        for index in cycle {
+            let settled = match kind {
+                // None of the convoys involved succeed, and what else is decided up front depends on the rule (see
+                // `ParadoxRule`). Everything else is resolved again without the convoys.
+                CycleKind::ConvoyParadox => match self.paradox_rule {
+                    _ if is_path(&index) => Some(false),
+                    ParadoxRule::Szykman => (self.convoyed[index]
+                        && self.orders[index].order_type == Move)
+                        .then_some(false),
+                    ParadoxRule::AllHold => {
+                        (self.orders[index].order_type == Move).then_some(false)
+                    }
+                    ParadoxRule::DptgBackup => None,
+                },
+                // Circular movement, where every unit moves.
+                CycleKind::Swap | CycleKind::Rotation => {
+                    (self.orders[index].order_type == Move).then_some(true)
+                }
+                CycleKind::Other => (self.orders[index].order_type == Move).then_some(false),
+            };
+
+            match settled {
+                Some(result) => {
+                    self.settle(index, result);
+
+                    // A move that fails outright can't arrive by convoy either.
+                    if !result && index < order_count {
+                        self.settle(order_count + index, false);
+                    }
+                }
+                None => self.states[index] = DecisionState::Unresolved,
+            }
+        }
+    }
+
+    fn is_dislodged(&mut self, index: usize) -> bool {
+        let order = self.orders[index];
+        if order.order_type == Move && self.resolve(index) {
+            return false;
+        }
+
+        self.moves_into(order.order_of)
+            .into_iter()
+            .any(|attacker| attacker != index && self.resolve(attacker))
+    }
+
+    // Writes the result of every decision back into the orders.
+    fn finish(mut self) -> Vec<Order> {
+        // Find the supports that counted for each order up front, as updating the order types below changes what they
+        // match. These are the same supports the strength of the order was worked out from, so a move doesn't count any
+        // from the player it is trying to dislodge. A move onto its own player's unit can only keep others out, which every
+        // support counts towards (see `prevent_strength`).
+        let counted_supports: Vec<Vec<usize>> = (0..self.orders.len())
+            .map(|index| {
+                let ignoring_owner_of = if self.orders[index].order_type == Move {
+                    self.staying_defender(index)
+                        .filter(|defender| !self.same_owner(index, *defender))
+                } else {
+                    None
+                };
+                self.counted_supports(index, ignoring_owner_of)
+            })
+            .collect();
+
+        for (index, supports) in counted_supports.into_iter().enumerate() {
+            for support in supports {
+                let supporter = self.orders[support].order_of;
+                self.orders[index].add_support(supporter);
+            }
+
+            let order_type = self.orders[index].order_type;
+            match order_type {
+                Move if !self.resolve(index) => {
+                    self.orders[index].order_type = Bounced;
+                }
+                Support if !self.resolve(index) => {
+                    self.orders[index].order_type = SupportCut;
+                }
+                _ => {}
+            }
+        }
+
+        // Every decision has been made, so work out who was dislodged. This has to wait until the order types above were
+        // updated, as the dislodging moves are found using them.
+        for index in 0..self.orders.len() {
+            self.orders[index].dislodged = self.is_dislodged(index);
+            self.orders[index].resolved = true;
+        }
+
+        self.orders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MapError;
+    use crate::map::Connection;
+    use crate::player::Player;
+
+    // The standard map with only the given units on it, each written as the power and the unit, i.e. ("France", "A Par").
+    fn board(units: &[(&str, &str)]) -> Map {
+        let mut map = Map::standard();
+        let occupied: Vec<ProvinceID> = map.occupied().map(|(id, _)| id).collect();
+        for id in occupied {
+            map.province_mut(id).unwrap().set_occupant(None);
+        }
+
+        let powers = Player::standard_powers();
+        for (power, unit) in units {
+            let owner = powers
+                .iter()
+                .find(|player| player.player_name() == *power)
+                .unwrap()
+                .player_id();
+            let (unit_type, province) = unit.split_once(' ').unwrap();
+            let unit_type = parse_unit_type(unit_type).unwrap();
+            let province = map.province_by_name(province).unwrap();
+            map.province_mut(province)
+                .unwrap()
+                .set_occupant(Some(Unit::new(owner, unit_type)));
+        }
+        map
+    }
+
+    fn id(map: &Map, name: &str) -> ProvinceID {
+        map.province_by_name(name).unwrap()
+    }
+
+    // Parses and resolves the orders with the default paradox rule.
+    fn resolve(map: &Map, orders: &[&str]) -> Vec<Order> {
+        let orders: Vec<Order> = orders
+            .iter()
+            .map(|order| Order::parse(order, map).unwrap())
+            .collect();
+        resolve_orders(&orders, map, ParadoxRule::default()).unwrap()
+    }
+
+    // The resolved order for the unit in the named province.
+    fn resolved<'a>(map: &Map, orders: &'a [Order], name: &str) -> &'a Order {
+        order_for(orders, id(map, name)).unwrap()
+    }
+
+    #[test]
+    fn equal_strengths_bounce() {
+        assert!(!dislodges(1, 1));
+        assert!(!dislodges(2, 2));
+        assert!(!wins_contest(2, &[1, 2]));
+    }
+
+    #[test]
+    fn strictly_greater_strength_succeeds() {
+        assert!(dislodges(2, 1));
+        assert!(!dislodges(1, 2));
+        assert!(wins_contest(3, &[2, 1, 2]));
+
+        // A move that nothing else is trying to make always gets in.
+        assert!(wins_contest(1, &[]));
+    }
+
+    #[test]
+    fn only_supports_that_counted_are_listed() {
+        let map = board(&[
+            ("Germany", "A Mun"),
+            ("Germany", "A Ruh"),
+            ("Germany", "A Bel"),
+            ("France", "A Pic"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Mun - Bur",
+                "A Ruh S A Mun - Bur",
+                "A Bel S A Mun - Bur",
+                "A Pic - Bel",
+            ],
+        );
+        let munich = resolved(&map, &orders, "Mun");
+
+        assert_eq!(resolved(&map, &orders, "Bel").outcome(), "cut");
+        assert_eq!(munich.outcome(), "success");
+        assert_eq!(munich.order_strength(), 2);
+        assert_eq!(munich.supporting_orders(), vec![id(&map, "Ruh")]);
+    }
+
+    #[test]
+    fn attack_on_a_convoying_fleet_that_bounces_does_not_disrupt_the_convoy() {
+        let map = board(&[
+            ("England", "F NTH"),
+            ("England", "A Yor"),
+            ("England", "F Edi"),
+            ("Germany", "F HEL"),
+            ("Germany", "F Den"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "F NTH C A Yor - Nwy",
+                "A Yor - Nwy",
+                "F Edi S F NTH",
+                "F HEL - NTH",
+                "F Den S F HEL - NTH",
+            ],
+        );
+
+        assert_eq!(resolved(&map, &orders, "HEL").outcome(), "bounced");
+        assert_eq!(resolved(&map, &orders, "NTH").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Yor").outcome(), "success");
+    }
+
+    // France's army in Gascony is ordered to Brest, which it could also reach by land, while its fleet in the Mid-Atlantic
+    // Ocean is dislodged by England.
+    fn disrupted_convoy_with_a_land_route(army_order: &str) -> Vec<Order> {
+        let map = board(&[
+            ("France", "A Gas"),
+            ("France", "F MAO"),
+            ("England", "F ENG"),
+            ("England", "F IRI"),
+        ]);
+        resolve(
+            &map,
+            &[
+                army_order,
+                "F MAO C A Gas - Bre",
+                "F ENG - MAO",
+                "F IRI S F ENG - MAO",
+            ],
+        )
+    }
+
+    #[test]
+    fn army_with_a_land_route_moves_over_land_when_its_convoy_is_disrupted() {
+        let map = Map::standard();
+        let orders = disrupted_convoy_with_a_land_route("A Gas - Bre");
+
+        assert!(resolved(&map, &orders, "MAO").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Gas").outcome(), "success");
+    }
+
+    #[test]
+    fn army_that_asked_for_a_convoy_does_not_move_over_land_when_it_is_disrupted() {
+        let map = Map::standard();
+        let orders = disrupted_convoy_with_a_land_route("A Gas - Bre via Convoy");
+
+        assert!(resolved(&map, &orders, "MAO").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Gas").outcome(), "bounced");
+    }
+
+    #[test]
+    fn parsing_records_whether_a_convoy_was_asked_for() {
+        let map = board(&[("England", "A Lon")]);
+        let (lon, nwy) = (id(&map, "Lon"), id(&map, "Nwy"));
+
+        let plain = Order::parse("A Lon - Nwy", &map).unwrap();
+        assert!(!plain.convoy_intended());
+        for input in [
+            "A Lon - Nwy via Convoy",
+            "A Lon - Nwy VC",
+            "a lon - nwy VIA CONVOY",
+        ] {
+            let order = Order::parse(input, &map).unwrap();
+            assert!(order.convoy_intended(), "{input}");
+            assert_eq!((order.order_of(), order.order_to()), (lon, nwy));
+        }
+
+        // Only moves can be convoyed.
+        assert_eq!(
+            Order::parse("A Lon H via Convoy", &map).err(),
+            Some(OrderError::InvalidFormat("A Lon H via Convoy".to_string()))
+        );
+    }
+
+    #[test]
+    fn graph_that_cannot_be_resolved_is_a_deadlock() {
+        let map = board(&[("France", "A Par"), ("Germany", "A Mun")]);
+        let orders = vec![
+            Order::parse("A Par - Bur", &map).unwrap(),
+            Order::parse("A Mun - Bur", &map).unwrap(),
+        ];
+        let (mut graph, mut nodes) = create_order_dependency_graph(&orders);
+
+        // A node with no order behind it, that one of the moves depends on.
+        let orphan = graph.add_node(());
+        graph.add_edge(NodeIndex::new(0), orphan, ());
+
+        assert_eq!(
+            resolve_all_non_dependant_edges(&mut graph, &mut nodes, &map, ParadoxRule::default())
+                .err(),
+            Some(AdjudicationError::Deadlock(vec![2]))
+        );
+    }
+
+    #[test]
+    fn resolving_again_does_not_count_supports_twice() {
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Mar"),
+            ("Germany", "A Bur"),
+        ]);
+        let first = resolve(&map, &["A Par - Bur", "A Mar S A Par - Bur", "A Bur H"]);
+        let second = resolve_orders(&first, &map, ParadoxRule::default()).unwrap();
+
+        for orders in [&first, &second] {
+            let paris = resolved(&map, orders, "Par");
+            assert_eq!(paris.order_strength(), 2);
+            assert_eq!(paris.supporting_orders(), vec![id(&map, "Mar")]);
+            assert!(resolved(&map, orders, "Bur").is_dislodged());
+        }
+    }
+
+    #[test]
+    fn army_cannot_enter_water_and_fleet_cannot_go_inland() {
+        let map = board(&[("France", "A Bre"), ("France", "F Pic")]);
+
+        assert!(
+            !Order::parse("A Bre - ENG", &map)
+                .unwrap()
+                .is_valid_for(&map)
+        );
+        assert!(
+            !Order::parse("F Pic - Par", &map)
+                .unwrap()
+                .is_valid_for(&map)
+        );
+        assert!(
+            Order::parse("A Bre - Par", &map)
+                .unwrap()
+                .is_valid_for(&map)
+        );
+        assert!(
+            Order::parse("F Pic - ENG", &map)
+                .unwrap()
+                .is_valid_for(&map)
+        );
+
+        let orders = resolve(&map, &["A Bre - ENG", "F Pic - Par"]);
+        assert_eq!(resolved(&map, &orders, "Bre").outcome(), "illegal");
+        assert_eq!(resolved(&map, &orders, "Pic").outcome(), "illegal");
+    }
+
+    #[test]
+    fn support_for_a_move_into_the_supporters_own_province_is_illegal() {
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Bur"),
+            ("Germany", "A Mun"),
+        ]);
+        let orders = resolve(
+            &map,
+            &["A Par - Bur", "A Bur S A Par - Bur", "A Mun S A Par - Bur"],
+        );
+        assert_eq!(resolved(&map, &orders, "Bur").order_type(), IllegalOrder);
+        assert_eq!(resolved(&map, &orders, "Bur").outcome(), "illegal");
+        // A support from somewhere else is still given.
+        assert_eq!(resolved(&map, &orders, "Mun").outcome(), "success");
+
+        // Even on a map that wrongly connects Spain to its own north coast, the fleet there can't support a move into Spain.
+        let mut map = board(&[("France", "F Spa/nc"), ("France", "A Gas")]);
+        let (spa, spa_nc) = (id(&map, "Spa"), id(&map, "Spa/nc"));
+        map.add_connection(Connection::fleet(spa, spa_nc).unwrap());
+        assert!(map.neighbors(spa_nc, &UnitType::Fleet).contains(&spa));
+        let orders = resolve(&map, &["A Gas - Spa", "F Spa/nc S A Gas - Spa"]);
+        assert_eq!(resolved(&map, &orders, "Spa/nc").order_type(), IllegalOrder);
+    }
+
+    #[test]
+    fn order_types_are_written_as_the_order_they_came_from() {
+        assert_eq!(Hold.to_string(), "Hold");
+        assert_eq!(IllegalOrder.to_string(), "Illegal order");
+        assert_eq!(SupportCut.to_string(), "Support (cut)");
+        assert_eq!(Bounced.to_string(), "Move (bounced)");
+    }
+
+    #[test]
+    fn illegal_orders_can_become_holds_that_are_supported() {
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Gas"),
+            ("Germany", "A Bur"),
+            ("Germany", "A Pic"),
+        ]);
+        // Paris isn't next to Munich, so the move could never be made.
+        let orders: Vec<Order> = [
+            "A Par - Mun",
+            "A Gas S A Par",
+            "A Bur - Par",
+            "A Pic S A Bur - Par",
+        ]
+        .iter()
+        .map(|order| Order::parse(order, &map).unwrap())
+        .collect();
+        let resolve_for = |illegal_orders_become_holds: bool| {
+            let mut config = VariantConfig::standard();
+            config.set_illegal_orders_become_holds(illegal_orders_become_holds);
+            resolve_orders_for_variant(&orders, &map, &config, false)
+                .unwrap()
+                .0
+        };
+
+        // An illegal order can't be supported, so Paris falls.
+        let illegal = resolve_for(false);
+        assert_eq!(resolved(&map, &illegal, "Par").order_type(), IllegalOrder);
+        assert!(resolved(&map, &illegal, "Par").is_dislodged());
+        assert_eq!(resolved(&map, &illegal, "Bur").outcome(), "success");
+
+        // As a hold, it is supported like any other, and the attack bounces.
+        let holds = resolve_for(true);
+        let paris = resolved(&map, &holds, "Par");
+        assert_eq!(paris.order_type(), Hold);
+        assert_eq!(paris.original_order_type(), Move);
+        assert!(!paris.is_dislodged());
+        assert_eq!(resolved(&map, &holds, "Bur").outcome(), "bounced");
+    }
+
+    #[test]
+    fn unit_on_the_board_decides_what_it_can_do_over_the_written_type() {
+        let map = board(&[("England", "F Lon"), ("England", "A Yor")]);
+
+        // The unit in London is a fleet, however the order describes it, and the army in York can be convoyed.
+        assert!(Order::parse("A Lon C A Yor - Bel", &map).is_ok());
+        assert!(Order::parse("F Lon C F Yor - Bel", &map).is_ok());
+        assert_eq!(
+            Order::parse("F Yor - Bel via Convoy", &map).map(|order| order.order_of()),
+            Ok(id(&map, "Yor"))
+        );
+
+        // With nobody in the provinces, the written types are all there is to go by.
+        assert!(Order::parse("F NTH C A Edi - Nwy", &map).is_ok());
+        assert_eq!(
+            Order::parse("A NTH C A Edi - Nwy", &map).err(),
+            Some(OrderError::UnitCannotPerform(id(&map, "NTH")))
+        );
+        assert_eq!(
+            Order::parse("F NTH C F Edi - Nwy", &map).err(),
+            Some(OrderError::UnitCannotPerform(id(&map, "Edi")))
+        );
+    }
+
+    #[test]
+    fn only_fleets_convoy_and_only_armies_are_convoyed() {
+        let map = board(&[
+            ("England", "A Yor"),
+            ("England", "F Lon"),
+            ("England", "F NTH"),
+            ("England", "A Edi"),
+        ]);
+
+        // An army can't convoy.
+        assert_eq!(
+            Order::parse("A Yor C A Edi - Nwy", &map).err(),
+            Some(OrderError::UnitCannotPerform(id(&map, "Yor")))
+        );
+        // A fleet can't be convoyed, whether by a convoy order or by asking for one.
+        assert_eq!(
+            Order::parse("F NTH C F Lon - Nwy", &map).err(),
+            Some(OrderError::UnitCannotPerform(id(&map, "Lon")))
+        );
+        assert_eq!(
+            Order::parse("F Lon - Nwy via Convoy", &map).err(),
+            Some(OrderError::UnitCannotPerform(id(&map, "Lon")))
+        );
+        assert!(
+            Order::parse("F NTH C A Edi - Nwy", &map)
+                .unwrap()
+                .is_valid_for(&map)
+        );
+
+        // An order built directly, without parsing, is still ruled illegal.
+        let orders = resolve_orders(
+            &[Order::convoy(
+                id(&map, "Yor"),
+                id(&map, "Edi"),
+                id(&map, "Nwy"),
+            )],
+            &map,
+            ParadoxRule::default(),
+        )
+        .unwrap();
+        assert_eq!(orders[0].outcome(), "illegal");
+    }
+
+    #[test]
+    fn fleet_can_only_support_into_provinces_next_to_its_own_coast() {
+        // Marseilles is next to the south coast of Spain, but not the north coast.
+        for (coast, supported) in [("Spa/nc", false), ("Spa/sc", true)] {
+            let fleet = format!("F {coast}");
+            let map = board(&[("France", "A Gas"), ("France", &fleet), ("Italy", "A Mar")]);
+            let support = format!("{fleet} S A Gas - Mar");
+            let orders = resolve(&map, &["A Gas - Mar", &support, "A Mar H"]);
+
+            assert_eq!(
+                Order::parse(&support, &map).unwrap().is_valid_for(&map),
+                supported,
+                "{coast}"
+            );
+            if supported {
+                assert!(resolved(&map, &orders, "Mar").is_dislodged());
+            } else {
+                assert_eq!(resolved(&map, &orders, coast).outcome(), "illegal");
+                assert_eq!(resolved(&map, &orders, "Gas").outcome(), "bounced");
+            }
+        }
+    }
+
+    // DATC 6.F.14, a simple convoy paradox: the army in Brest only arrives to cut the support from London if the fleet in
+    // the English Channel isn't dislodged, which it is only if the support isn't cut.
+    fn simple_convoy_paradox(rule: ParadoxRule) -> (Map, Vec<Order>) {
+        let map = board(&[
+            ("England", "F Lon"),
+            ("England", "F Wal"),
+            ("France", "A Bre"),
+            ("France", "F ENG"),
+        ]);
+        let orders: Vec<Order> = [
+            "F Lon S F Wal - ENG",
+            "F Wal - ENG",
+            "A Bre - Lon",
+            "F ENG C A Bre - Lon",
+        ]
+        .iter()
+        .map(|order| Order::parse(order, &map).unwrap())
+        .collect();
+        let orders = resolve_orders(&orders, &map, rule).unwrap();
+        (map, orders)
+    }
+
+    #[test]
+    fn paradox_rule_decides_a_convoy_paradox() {
+        // Both rules stop the convoy, but only the Szykman rule lets the attack on the convoying fleet go ahead.
+        let (map, szykman) = simple_convoy_paradox(ParadoxRule::Szykman);
+        assert_eq!(resolved(&map, &szykman, "Bre").outcome(), "bounced");
+        assert_eq!(resolved(&map, &szykman, "Wal").outcome(), "success");
+        assert!(resolved(&map, &szykman, "ENG").is_dislodged());
+
+        let (map, all_hold) = simple_convoy_paradox(ParadoxRule::AllHold);
+        assert_eq!(resolved(&map, &all_hold, "Bre").outcome(), "bounced");
+        assert_eq!(resolved(&map, &all_hold, "Wal").outcome(), "bounced");
+        assert!(!resolved(&map, &all_hold, "ENG").is_dislodged());
+    }
+
+    #[test]
+    fn pandins_paradox_leaves_the_convoying_fleet_in_place_under_every_rule() {
+        let map = board(&[
+            ("England", "F Lon"),
+            ("England", "F Wal"),
+            ("France", "A Bre"),
+            ("France", "F ENG"),
+            ("Germany", "F NTH"),
+            ("Germany", "F Bel"),
+        ]);
+        let orders: Vec<Order> = [
+            "F Lon S F Wal - ENG",
+            "F Wal - ENG",
+            "A Bre - Lon",
+            "F ENG C A Bre - Lon",
+            "F NTH S F Bel - ENG",
+            "F Bel - ENG",
+        ]
+        .iter()
+        .map(|order| Order::parse(order, &map).unwrap())
+        .collect();
+
+        for rule in [
+            ParadoxRule::Szykman,
+            ParadoxRule::AllHold,
+            ParadoxRule::DptgBackup,
+        ] {
+            let orders = resolve_orders(&orders, &map, rule).unwrap();
+            for (province, outcome) in [
+                ("Bre", "bounced"),
+                ("Wal", "bounced"),
+                ("Bel", "bounced"),
+                ("ENG", "success"),
+            ] {
+                assert_eq!(
+                    resolved(&map, &orders, province).outcome(),
+                    outcome,
+                    "{rule:?}: {province}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_valid_for_checks_orders_without_resolving_them() {
+        let map = board(&[
+            ("England", "A Lon"),
+            ("England", "F NTH"),
+            ("England", "F IRI"),
+            ("England", "A Edi"),
+        ]);
+        let valid = |order: &str| Order::parse(order, &map).unwrap().is_valid_for(&map);
+
+        assert!(valid("A Lon - Wal"));
+        assert!(valid("F NTH C A Lon - Nwy"));
+        // The army could be convoyed to Norway, so the move is valid even before the convoy is ordered.
+        assert!(valid("A Lon - Nwy"));
+
+        // Edinburgh can't move to Wales itself, so it can't support a move there.
+        assert!(!valid("A Edi S A Lon - Wal"));
+        // The Irish Sea isn't on any route from London to Norway.
+        assert!(!valid("F IRI C A Lon - Nwy"));
+        // There is no unit in Paris to give the order to.
+        assert!(!Order::move_order(id(&map, "Par"), id(&map, "Bur")).is_valid_for(&map));
+    }
+
+    #[test]
+    fn convoy_succeeds_while_one_of_its_routes_is_intact() {
+        // England can convoy from London to Belgium through either the English Channel or the North Sea. Both fleets are
+        // attacked, but only the one in the Channel is dislodged.
+        let map = board(&[
+            ("England", "A Lon"),
+            ("England", "F ENG"),
+            ("England", "F NTH"),
+            ("France", "F Bre"),
+            ("France", "F MAO"),
+            ("Germany", "F HEL"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Lon - Bel",
+                "F ENG C A Lon - Bel",
+                "F NTH C A Lon - Bel",
+                "F Bre - ENG",
+                "F MAO S F Bre - ENG",
+                "F HEL - NTH",
+            ],
+        );
+
+        assert!(resolved(&map, &orders, "ENG").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "HEL").outcome(), "bounced");
+        assert!(!resolved(&map, &orders, "NTH").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Lon").outcome(), "success");
+    }
+
+    #[test]
+    fn support_holds_add_up_and_hold_off_an_equal_attack() {
+        let map = board(&[
+            ("Germany", "A Mun"),
+            ("Germany", "A Ruh"),
+            ("Germany", "A Kie"),
+            ("France", "A Bur"),
+            ("Austria", "A Tyr"),
+            ("Austria", "A Boh"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Mun H",
+                "A Ruh S A Mun",
+                "A Kie S A Mun",
+                "A Bur - Mun",
+                "A Tyr S A Bur - Mun",
+                "A Boh S A Bur - Mun",
+            ],
+        );
+        let munich = resolved(&map, &orders, "Mun");
+
+        assert_eq!(munich.order_strength(), 3);
+        assert!(!munich.is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Bur").order_strength(), 3);
+        assert_eq!(resolved(&map, &orders, "Bur").outcome(), "bounced");
+    }
+
+    #[test]
+    fn move_to_own_province_is_a_hold_without_a_self_loop() {
+        let map = board(&[("France", "A Par")]);
+        let orders = resolve(&map, &["A Par - Par"]);
+        let paris = resolved(&map, &orders, "Par");
+        assert_eq!(paris.order_type(), Hold);
+        assert_eq!(paris.original_order_type(), Move);
+
+        let mut marked = vec![Order::parse("A Par - Par", &map).unwrap()];
+        mark_illegal_orders(&mut marked, &map, false);
+        let (graph, _) = create_order_dependency_graph(&marked);
+        assert!(
+            graph
+                .edge_references()
+                .all(|edge| edge.source() != edge.target())
+        );
+    }
+
+    #[test]
+    fn support_for_a_move_to_own_province_does_not_hold_it() {
+        let map = board(&[
+            ("England", "A Yor"),
+            ("England", "A Lvp"),
+            ("Germany", "F Lon"),
+            ("Germany", "A Wal"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Yor - Yor",
+                "A Lvp S A Yor - Yor",
+                "F Lon - Yor",
+                "A Wal S F Lon - Yor",
+            ],
+        );
+
+        assert_eq!(resolved(&map, &orders, "Lvp").outcome(), "void");
+        assert!(resolved(&map, &orders, "Yor").is_dislodged());
+    }
+
+    #[test]
+    fn orders_on_an_invalid_map_are_not_resolved() {
+        // Paris claims to be a coast of Burgundy, which doesn't list it as one.
+        let mut map = board(&[("France", "A Par")]);
+        let (par, bur) = (id(&map, "Par"), id(&map, "Bur"));
+        map.province_mut(par).unwrap().set_coast_of(bur);
+
+        let orders = [Order::hold(par)];
+        assert_eq!(
+            resolve_orders(&orders, &map, ParadoxRule::default()).unwrap_err(),
+            AdjudicationError::InvalidMap(MapError::InvalidCoast(par))
+        );
+    }
+
+    // Germany attacks Burgundy from the Ruhr with support from Munich, while Austria may attack Munich from Tyrolia.
+    fn supported_attack_on_burgundy(austria: &str) -> (Map, Vec<Order>) {
+        let map = board(&[
+            ("Germany", "A Ruh"),
+            ("Germany", "A Mun"),
+            ("France", "A Bur"),
+            ("Austria", "A Tyr"),
+        ]);
+        let orders = resolve(
+            &map,
+            &["A Ruh - Bur", "A Mun S A Ruh - Bur", "A Bur H", austria],
+        );
+        (map, orders)
+    }
+
+    #[test]
+    fn move_that_bounces_still_cuts_support() {
+        let (map, orders) = supported_attack_on_burgundy("A Tyr - Mun");
+        assert_eq!(resolved(&map, &orders, "Tyr").outcome(), "bounced");
+        assert_eq!(resolved(&map, &orders, "Mun").outcome(), "cut");
+        assert_eq!(resolved(&map, &orders, "Ruh").outcome(), "bounced");
+        assert!(!resolved(&map, &orders, "Bur").is_dislodged());
+
+        let (map, orders) = supported_attack_on_burgundy("A Tyr H");
+        assert_eq!(resolved(&map, &orders, "Ruh").outcome(), "success");
+        assert!(resolved(&map, &orders, "Bur").is_dislodged());
+    }
+
+    // Parses and resolves the orders with the default paradox rule, returning only how much work it took.
+    fn stats_for(map: &Map, orders: &[&str]) -> ResolutionStats {
+        let orders: Vec<Order> = orders
+            .iter()
+            .map(|order| Order::parse(order, map).unwrap())
+            .collect();
+        resolve_orders_with_stats(&orders, map, ParadoxRule::default())
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn simple_turn_takes_few_iterations_and_no_cycles() {
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Mar"),
+            ("Germany", "A Mun"),
+        ]);
+        let stats = stats_for(&map, &["A Par - Pic", "A Mar H", "A Mun - Ruh"]);
+
+        assert_eq!(stats.orders_resolved, 3);
+        assert!(
+            stats.iterations <= 3,
+            "took {} iterations",
+            stats.iterations
+        );
+        assert_eq!(stats.cycles_detected, 0);
+    }
+
+    #[test]
+    fn rotation_of_units_is_a_detected_cycle() {
+        let map = board(&[
+            ("Germany", "A Bur"),
+            ("Germany", "A Mun"),
+            ("Germany", "A Ruh"),
+        ]);
+        let stats = stats_for(&map, &["A Bur - Mun", "A Mun - Ruh", "A Ruh - Bur"]);
+
+        assert_eq!(stats.orders_resolved, 3);
+        assert!(stats.cycles_detected >= 1);
+        assert_eq!(stats.cycle_kinds, vec![CycleKind::Rotation]);
+    }
+
+    #[test]
+    fn only_supports_that_are_not_cut_add_to_strength() {
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Pic"),
+            ("France", "A Gas"),
+            ("France", "A Mar"),
+            ("France", "A Bel"),
+            ("France", "A Ruh"),
+            ("Germany", "A Bur"),
+            ("Germany", "A Hol"),
+            ("Germany", "A Kie"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Par - Bur",
+                "A Pic S A Par - Bur",
+                "A Gas S A Par - Bur",
+                "A Mar S A Par - Bur",
+                "A Bel S A Par - Bur",
+                "A Ruh S A Par - Bur",
+                "A Bur H",
+                "A Hol - Bel",
+                "A Kie - Ruh",
+            ],
+        );
+
+        // Five supports, two of them cut.
+        for name in ["Bel", "Ruh"] {
+            assert_eq!(resolved(&map, &orders, name).outcome(), "cut", "{name}");
+        }
+        let paris = resolved(&map, &orders, "Par");
+        assert_eq!(paris.order_strength(), 4);
+        assert_eq!(paris.outcome(), "success");
+        assert!(resolved(&map, &orders, "Bur").is_dislodged());
+    }
+
+    #[test]
+    fn order_for_finds_only_the_order_given_to_that_province() {
+        let map = board(&[("France", "A Par"), ("Russia", "F StP/sc")]);
+        let mut orders: Vec<Order> = ["A Par - Bur", "F StP/sc - BOT"]
+            .iter()
+            .map(|order| Order::parse(order, &map).unwrap())
+            .collect();
+
+        assert_eq!(
+            order_for(&orders, id(&map, "Par")).map(|order| order.order_to()),
+            Some(id(&map, "Bur"))
+        );
+        assert!(order_for(&orders, id(&map, "StP/sc")).is_some());
+        assert!(order_for(&orders, id(&map, "StP")).is_none());
+        assert!(order_for(&orders, id(&map, "Mar")).is_none());
+
+        order_for_mut(&mut orders, id(&map, "Par"))
+            .unwrap()
+            .set_order_type(OrderType::Hold);
+        assert_eq!(orders[0].order_type(), OrderType::Hold);
+        assert!(order_for_mut(&mut orders, id(&map, "Mar")).is_none());
+    }
+
+    #[test]
+    fn trace_lists_each_decision_in_the_order_it_was_made() {
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Mar"),
+            ("Germany", "A Mun"),
+        ]);
+        let orders: Vec<Order> = ["A Par - Bur", "A Mar S A Par - Bur", "A Mun - Bur"]
+            .iter()
+            .map(|order| Order::parse(order, &map).unwrap())
+            .collect();
+        let (par, mar, mun, bur) = (
+            id(&map, "Par"),
+            id(&map, "Mar"),
+            id(&map, "Mun"),
+            id(&map, "Bur"),
+        );
+
+        let (_, _, trace) = resolve_orders_traced(&orders, &map, ParadoxRule::default()).unwrap();
+        let position = |step: ResolutionStep| {
+            trace
+                .iter()
+                .position(|traced| *traced == step)
+                .unwrap_or_else(|| panic!("{step:?} should be in {trace:?}"))
+        };
+        let support = position(ResolutionStep::SupportGiven(mar));
+        let success = position(ResolutionStep::MoveSucceeded { from: par, to: bur });
+        let bounce = position(ResolutionStep::MoveBounced { from: mun, to: bur });
+        // The support has to be decided before either move can be.
+        assert!(support < success && support < bounce);
+
+        // A cycle is noted where it was broken.
+        let map = board(&[
+            ("Germany", "A Bur"),
+            ("Germany", "A Mun"),
+            ("Germany", "A Ruh"),
+        ]);
+        let orders: Vec<Order> = ["A Bur - Mun", "A Mun - Ruh", "A Ruh - Bur"]
+            .iter()
+            .map(|order| Order::parse(order, &map).unwrap())
+            .collect();
+        let (_, _, trace) = resolve_orders_traced(&orders, &map, ParadoxRule::default()).unwrap();
+        assert!(trace.contains(&ResolutionStep::CycleResolved(CycleKind::Rotation)));
+        assert!(trace.contains(&ResolutionStep::MoveSucceeded {
+            from: id(&map, "Bur"),
+            to: id(&map, "Mun")
+        }));
+    }
+
+    #[test]
+    fn convoy_of_an_overland_move_is_only_given_by_the_armys_own_fleet() {
+        let map = board(&[
+            ("France", "A Bel"),
+            ("England", "F NTH"),
+            ("France", "F ENG"),
+            ("England", "A Pic"),
+        ]);
+
+        // Another player's convoy isn't needed for a move straight into Holland, so it isn't given.
+        let orders = resolve(&map, &["A Bel - Hol", "F NTH C A Bel - Hol"]);
+        assert_eq!(
+            resolved(&map, &orders, "NTH").order_type(),
+            RequiredOrderNotGiven
+        );
+        assert_eq!(resolved(&map, &orders, "NTH").outcome(), "void");
+        assert_eq!(resolved(&map, &orders, "Bel").outcome(), "success");
+
+        // France's own fleet convoying the army means the army goes by convoy, so it passes the English army instead of
+        // bouncing off it.
+        let orders = resolve(&map, &["A Bel - Pic", "F ENG C A Bel - Pic", "A Pic - Bel"]);
+        assert_eq!(resolved(&map, &orders, "ENG").order_type(), Convoy);
+        assert_eq!(resolved(&map, &orders, "Bel").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Pic").outcome(), "success");
+
+        // Without the convoy, the two armies meet head to head.
+        let orders = resolve(&map, &["A Bel - Pic", "F ENG H", "A Pic - Bel"]);
+        assert_eq!(resolved(&map, &orders, "Bel").outcome(), "bounced");
+        assert_eq!(resolved(&map, &orders, "Pic").outcome(), "bounced");
+    }
+
+    #[test]
+    fn swap_by_convoy_is_a_swap_cycle_and_both_moves_succeed() {
+        let map = board(&[
+            ("England", "A Nwy"),
+            ("England", "F SKA"),
+            ("Russia", "A Swe"),
+        ]);
+        let order_text = ["A Nwy - Swe", "F SKA C A Nwy - Swe", "A Swe - Nwy"];
+        let stats = stats_for(&map, &order_text);
+        assert_eq!(stats.cycle_kinds, vec![CycleKind::Swap]);
+
+        let orders = resolve(&map, &order_text);
+        assert_eq!(resolved(&map, &orders, "Nwy").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Swe").outcome(), "success");
+    }
+
+    #[test]
+    fn convoy_paradoxes_are_classified_as_convoy_paradoxes() {
+        let map = board(&[
+            ("England", "F Lon"),
+            ("England", "F Wal"),
+            ("France", "A Bre"),
+            ("France", "F ENG"),
+        ]);
+        let stats = stats_for(
+            &map,
+            &[
+                "F Lon S F Wal - ENG",
+                "F Wal - ENG",
+                "A Bre - Lon",
+                "F ENG C A Bre - Lon",
+            ],
+        );
+        assert_eq!(stats.cycle_kinds, vec![CycleKind::ConvoyParadox]);
+
+        // Pandin's paradox: the cycle runs through the convoy even though neither attack on the fleet can win.
+        let map = board(&[
+            ("England", "F Lon"),
+            ("England", "F Wal"),
+            ("France", "A Bre"),
+            ("France", "F ENG"),
+            ("Germany", "F NTH"),
+            ("Germany", "F Bel"),
+        ]);
+        let stats = stats_for(
+            &map,
+            &[
+                "F Lon S F Wal - ENG",
+                "F Wal - ENG",
+                "A Bre - Lon",
+                "F ENG C A Bre - Lon",
+                "F NTH S F Bel - ENG",
+                "F Bel - ENG",
+            ],
+        );
+        assert!(!stats.cycle_kinds.is_empty());
+        assert!(
+            stats
+                .cycle_kinds
+                .iter()
+                .all(|kind| *kind == CycleKind::ConvoyParadox),
+            "{:?}",
+            stats.cycle_kinds
+        );
+    }
+
+    #[test]
+    fn only_a_fleet_at_sea_can_convoy() {
+        let map = board(&[
+            ("England", "A Yor"),
+            ("England", "F Lon"),
+            ("England", "F NTH"),
+        ]);
+        let orders = resolve(
+            &map,
+            &["A Yor - Bel", "F NTH C A Yor - Bel", "F Lon C A Yor - Bel"],
+        );
+
+        assert_eq!(resolved(&map, &orders, "NTH").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Lon").order_type(), IllegalOrder);
+        assert_eq!(resolved(&map, &orders, "Lon").outcome(), "illegal");
+        assert_eq!(resolved(&map, &orders, "Yor").outcome(), "success");
+    }
+
+    // England convoys an army into Belgium, whose support would let Germany dislodge France from Burgundy. Germany also
+    // attacks the convoying fleet, with `north_sea_attack` as the extra German orders.
+    fn convoyed_attack_on_a_supporter(north_sea_attack: &[&str]) -> (Map, Vec<Order>) {
+        let map = board(&[
+            ("England", "A Lon"),
+            ("England", "F NTH"),
+            ("France", "A Bur"),
+            ("Germany", "A Ruh"),
+            ("Germany", "A Bel"),
+            ("Germany", "F HEL"),
+            ("Germany", "F Den"),
+        ]);
+        let mut given = vec![
+            "A Lon - Bel",
+            "F NTH C A Lon - Bel",
+            "A Bur H",
+            "A Ruh - Bur",
+            "A Bel S A Ruh - Bur",
+        ];
+        given.extend_from_slice(north_sea_attack);
+        let orders = resolve(&map, &given);
+        (map, orders)
+    }
+
+    #[test]
+    fn convoyed_attacker_cuts_support_only_if_the_convoy_arrives() {
+        let (map, orders) = convoyed_attack_on_a_supporter(&["F HEL H", "F Den H"]);
+        assert_eq!(resolved(&map, &orders, "Bel").outcome(), "cut");
+        assert_eq!(resolved(&map, &orders, "Ruh").outcome(), "bounced");
+        assert!(!resolved(&map, &orders, "Bur").is_dislodged());
+
+        let (map, orders) = convoyed_attack_on_a_supporter(&["F HEL - NTH", "F Den S F HEL - NTH"]);
+        assert!(resolved(&map, &orders, "NTH").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Bel").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Ruh").outcome(), "success");
+        assert!(resolved(&map, &orders, "Bur").is_dislodged());
+    }
+
+    #[test]
+    fn move_by_convoy_from_inland_is_illegal() {
+        let map = board(&[("Germany", "A Mun"), ("Germany", "A Kie")]);
+        for (name, borders_sea) in [("Mun", false), ("Par", false), ("Kie", true), ("StP", true)] {
+            assert_eq!(map.borders_sea(id(&map, name)), borders_sea, "{name}");
+        }
+
+        let orders = resolve(&map, &["A Mun - Ber via Convoy", "A Kie - Den via Convoy"]);
+        assert_eq!(resolved(&map, &orders, "Mun").order_type(), IllegalOrder);
+        assert_eq!(resolved(&map, &orders, "Mun").outcome(), "illegal");
+        // Kiel is on the coast, so the move is only let down by there being no fleets to convoy it.
+        assert_ne!(resolved(&map, &orders, "Kie").order_type(), IllegalOrder);
+
+        // Without asking for a convoy, the army just walks.
+        let orders = resolve(&map, &["A Mun - Ber", "A Kie H"]);
+        assert_eq!(resolved(&map, &orders, "Mun").outcome(), "success");
+    }
+
+    // The type and the three provinces of an order, which is all the constructors set apart from the defaults.
+    fn fields(order: &Order) -> (OrderType, ProvinceID, ProvinceID, ProvinceID) {
+        (
+            order.order_type(),
+            order.order_of(),
+            order.order_from(),
+            order.order_to(),
+        )
+    }
+
+    #[test]
+    fn constructors_set_the_provinces_and_defaults() {
+        assert_eq!(fields(&Order::hold(1)), (Hold, 1, 1, 1));
+        assert_eq!(fields(&Order::move_order(1, 2)), (Move, 1, 1, 2));
+        assert_eq!(fields(&Order::support(3, 1, 2)), (Support, 3, 1, 2));
+        assert_eq!(fields(&Order::support(3, 1, 1)), (Support, 3, 1, 1));
+        assert_eq!(fields(&Order::convoy(4, 1, 5)), (Convoy, 4, 1, 5));
+
+        for order in [
+            Order::hold(1),
+            Order::move_order(1, 2),
+            Order::convoy(4, 1, 5),
+        ] {
+            assert_eq!(order.original_order_type(), order.order_type());
+            assert_eq!(order.order_strength(), 1);
+            assert!(!order.is_resolved());
+            assert!(!order.is_dislodged());
+            assert!(!order.convoy_intended());
+            assert!(order.supporting_orders().is_empty());
+        }
+    }
+
+    // Germany's army in Munich supports Burgundy's attack on Ruhr, while Ruhr attacks Munich back, with support from
+    // Kiel when `supported` is set.
+    fn supporter_attacked_by_its_target(supported: bool) -> (Map, Vec<Order>) {
+        let map = board(&[
+            ("France", "A Bur"),
+            ("France", "A Mun"),
+            ("Germany", "A Ruh"),
+            ("Germany", "A Kie"),
+        ]);
+        let kiel = if supported {
+            "A Kie S A Ruh - Mun"
+        } else {
+            "A Kie H"
+        };
+        let orders = resolve(
+            &map,
+            &["A Bur - Ruh", "A Mun S A Bur - Ruh", "A Ruh - Mun", kiel],
+        );
+        (map, orders)
+    }
+
+    #[test]
+    fn target_of_a_support_cannot_cut_it() {
+        let (map, orders) = supporter_attacked_by_its_target(false);
+
+        assert_eq!(resolved(&map, &orders, "Mun").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Bur").outcome(), "success");
+        assert!(resolved(&map, &orders, "Ruh").is_dislodged());
+        assert!(!resolved(&map, &orders, "Mun").is_dislodged());
+    }
+
+    #[test]
+    fn target_of_a_support_cuts_it_by_dislodging_the_supporter() {
+        let (map, orders) = supporter_attacked_by_its_target(true);
+
+        assert!(resolved(&map, &orders, "Mun").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Mun").order_type(), SupportCut);
+        assert_eq!(resolved(&map, &orders, "Bur").order_strength(), 1);
+        assert_eq!(resolved(&map, &orders, "Ruh").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Bur").outcome(), "success");
+        assert!(!resolved(&map, &orders, "Ruh").is_dislodged());
+    }
+
+    #[test]
+    fn fleet_that_does_not_convoy_breaks_the_route() {
+        // The fleet in the Mid-Atlantic Ocean is on the only route from Wales to Gascony, but holds.
+        let map = board(&[
+            ("England", "A Wal"),
+            ("England", "F ENG"),
+            ("England", "F MAO"),
+        ]);
+        let given = ["A Wal - Gas", "F ENG C A Wal - Gas", "F MAO H"];
+        let parsed: Vec<Order> = given
+            .iter()
+            .map(|order| Order::parse(order, &map).unwrap())
+            .collect();
+        assert_eq!(
+            convoying_fleets(&parsed, id(&map, "Wal"), id(&map, "Gas")),
+            vec![id(&map, "ENG")]
+        );
+
+        let orders = resolve(&map, &given);
+        assert_eq!(resolved(&map, &orders, "Wal").outcome(), "bounced");
+
+        let orders = resolve(
+            &map,
+            &["A Wal - Gas", "F ENG C A Wal - Gas", "F MAO C A Wal - Gas"],
+        );
+        assert_eq!(resolved(&map, &orders, "Wal").outcome(), "success");
+    }
+
+    #[test]
+    fn coastal_province_holds_one_unit_of_either_type() {
+        let map = board(&[("France", "A Bre"), ("England", "F ENG")]);
+        let orders = resolve(&map, &["A Bre H", "F ENG - Bre"]);
+        assert_eq!(resolved(&map, &orders, "ENG").outcome(), "bounced");
+        assert!(!resolved(&map, &orders, "Bre").is_dislodged());
+
+        let map = board(&[("France", "F Bre"), ("England", "A Pic")]);
+        let orders = resolve(&map, &["F Bre H", "A Pic - Bre"]);
+        assert_eq!(resolved(&map, &orders, "Pic").outcome(), "bounced");
+        assert!(!resolved(&map, &orders, "Bre").is_dislodged());
+
+        // Units of both types can't be placed there together either: the second one replaces the first.
+        let mut map = board(&[("France", "A Bre")]);
+        let brest = id(&map, "Bre");
+        map.province_mut(brest)
+            .unwrap()
+            .set_occupant(Some(Unit::new(2, UnitType::Fleet)));
+        assert_eq!(map.occupied().filter(|(id, _)| *id == brest).count(), 1);
+        assert_eq!(map.occupant_owner(brest), Some(2));
+    }
+
+    // France holds Burgundy, with Paris also ordered into it, while Germany attacks it from Munich with support from Ruhr.
+    fn friendly_and_foreign_attacks_on_burgundy(marseilles: &str) -> (Map, Vec<Order>) {
+        let map = board(&[
+            ("France", "A Bur"),
+            ("France", "A Par"),
+            ("France", "A Mar"),
+            ("Germany", "A Mun"),
+            ("Germany", "A Ruh"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Bur H",
+                "A Par - Bur",
+                marseilles,
+                "A Mun - Bur",
+                "A Ruh S A Mun - Bur",
+            ],
+        );
+        (map, orders)
+    }
+
+    #[test]
+    fn support_from_the_defending_player_is_not_reported_as_counted() {
+        let map = board(&[
+            ("Germany", "A Mun"),
+            ("Germany", "A Ruh"),
+            ("France", "A Mar"),
+            ("France", "A Bur"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Mun - Bur",
+                "A Ruh S A Mun - Bur",
+                "A Mar S A Mun - Bur",
+                "A Bur H",
+            ],
+        );
+
+        // France's support can't help dislodge its own unit, so only Ruhr's counted.
+        let munich = resolved(&map, &orders, "Mun");
+        assert_eq!(munich.outcome(), "success");
+        assert_eq!(munich.order_strength(), 2);
+        assert_eq!(munich.supporting_orders(), vec![id(&map, "Ruh")]);
+        assert!(resolved(&map, &orders, "Bur").is_dislodged());
+    }
+
+    #[test]
+    fn support_hold_only_matters_against_a_foreign_attack() {
+        let (map, orders) = friendly_and_foreign_attacks_on_burgundy("A Mar S A Bur");
+        assert_eq!(resolved(&map, &orders, "Bur").order_strength(), 2);
+        assert!(!resolved(&map, &orders, "Bur").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Par").outcome(), "bounced");
+        assert_eq!(resolved(&map, &orders, "Mun").outcome(), "bounced");
+
+        let (map, orders) = friendly_and_foreign_attacks_on_burgundy("A Mar H");
+        assert!(resolved(&map, &orders, "Bur").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Par").outcome(), "bounced");
+        assert_eq!(resolved(&map, &orders, "Mun").outcome(), "success");
+
+        // However strong a friendly move is, it never dislodges France's own unit.
+        let map = board(&[
+            ("France", "A Bur"),
+            ("France", "A Par"),
+            ("France", "A Mar"),
+        ]);
+        let orders = resolve(&map, &["A Bur H", "A Par - Bur", "A Mar S A Par - Bur"]);
+        assert_eq!(resolved(&map, &orders, "Par").order_strength(), 2);
+        assert!(!resolved(&map, &orders, "Bur").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Par").outcome(), "bounced");
+    }
+
+    #[test]
+    fn beleaguered_convoying_fleet_keeps_its_convoy() {
+        // Germany and Russia both attack the North Sea with equal strength, so neither gets in.
+        let map = board(&[
+            ("England", "A Lon"),
+            ("England", "F NTH"),
+            ("Germany", "F HEL"),
+            ("Germany", "F Den"),
+            ("Russia", "F Nwy"),
+            ("Russia", "F SKA"),
+        ]);
+        let orders = resolve(
+            &map,
+            &[
+                "A Lon - Bel",
+                "F NTH C A Lon - Bel",
+                "F HEL - NTH",
+                "F Den S F HEL - NTH",
+                "F Nwy - NTH",
+                "F SKA S F Nwy - NTH",
+            ],
+        );
+
+        assert_eq!(resolved(&map, &orders, "HEL").outcome(), "bounced");
+        assert_eq!(resolved(&map, &orders, "Nwy").outcome(), "bounced");
+        assert!(!resolved(&map, &orders, "NTH").is_dislodged());
+        assert_eq!(resolved(&map, &orders, "Lon").outcome(), "success");
+    }
+
+    #[test]
+    fn move_to_a_coast_that_is_not_adjacent_needs_fleets_to_convoy_it() {
+        let with_fleet = board(&[("England", "A Lon"), ("England", "F NTH")]);
+        let orders = resolve(&with_fleet, &["A Lon - Bel", "F NTH C A Lon - Bel"]);
+        assert_eq!(resolved(&with_fleet, &orders, "Lon").outcome(), "success");
+
+        let without_fleet = board(&[("England", "A Lon")]);
+        let orders = resolve(&without_fleet, &["A Lon - Bel"]);
+        assert_eq!(
+            resolved(&without_fleet, &orders, "Lon").order_type(),
+            IllegalOrder
+        );
+        assert!(
+            !Order::parse("A Lon - Bel", &without_fleet)
+                .unwrap()
+                .is_valid_for(&without_fleet)
+        );
+    }
+
+    #[test]
+    fn move_into_a_province_being_left_follows_the_unit_out() {
+        let map = board(&[("France", "A Par"), ("France", "A Bur")]);
+        let orders = resolve(&map, &["A Par - Bur", "A Bur - Mun"]);
+        assert_eq!(resolved(&map, &orders, "Bur").outcome(), "success");
+        assert_eq!(resolved(&map, &orders, "Par").outcome(), "success");
+
+        // If the unit in front can't leave, the one following it can't get in either.
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Bur"),
+            ("Germany", "A Mun"),
+        ]);
+        let orders = resolve(&map, &["A Par - Bur", "A Bur - Mun", "A Mun H"]);
+        assert_eq!(resolved(&map, &orders, "Bur").outcome(), "bounced");
+        assert_eq!(resolved(&map, &orders, "Par").outcome(), "bounced");
+    }
+
+    #[test]
+    fn coasts_can_be_written_in_several_ways() {
+        let map = Map::standard();
+        let south_coast = id(&map, "Stp/sc");
+        for order in ["F StP/sc - BOT", "F StP(sc) - BOT", "F stp/SC - BOT"] {
+            let parsed = Order::parse(order, &map).unwrap();
+            assert_eq!(parsed.order_of(), south_coast, "{order}");
+        }
+        assert_eq!(map.province_by_name("Stp SC"), Some(south_coast));
+        assert_eq!(map.province_by_name("StP (sc)"), Some(south_coast));
+
+        assert_eq!(
+            Order::parse("F StP/wc - BOT", &map).err(),
+            Some(OrderError::UnknownCoast("StP/wc".to_string()))
+        );
+        assert_eq!(
+            Order::parse("A Par/nc - Bur", &map).err(),
+            Some(OrderError::UnknownProvince("Par/nc".to_string()))
+        );
+    }
+
+    // Every ordering of `items`.
+    fn permutations<T: Copy>(items: &[T]) -> Vec<Vec<T>> {
+        if items.len() <= 1 {
+            return vec![items.to_vec()];
+        }
+        (0..items.len())
+            .flat_map(|first| {
+                let mut rest = items.to_vec();
+                let item = rest.remove(first);
+                permutations(&rest).into_iter().map(move |mut tail| {
+                    tail.insert(0, item);
+                    tail
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn equal_strength_contest_bounces_whatever_order_the_orders_are_given_in() {
+        let map = board(&[
+            ("France", "A Par"),
+            ("France", "A Gas"),
+            ("Germany", "A Mun"),
+            ("Germany", "A Ruh"),
+        ]);
+        let given = [
+            "A Par - Bur",
+            "A Gas S A Par - Bur",
+            "A Mun - Bur",
+            "A Ruh S A Mun - Bur",
+        ];
+
+        for ordering in permutations(&given) {
+            let orders = resolve(&map, &ordering);
+            let outcomes: Vec<&str> = ["Par", "Gas", "Mun", "Ruh"]
+                .iter()
+                .map(|name| resolved(&map, &orders, name).outcome())
+                .collect();
+            assert_eq!(
+                outcomes,
+                vec!["bounced", "success", "bounced", "success"],
+                "{ordering:?}"
+            );
+        }
     }
 }
@@ -0,0 +1,124 @@
+use crate::{
+    error::{MapError, OrderError},
+    map::Map,
+    province::{ProvinceID, ProvinceType},
+    unit::UnitType,
+};
+
+// Splits an order into its parts. The dash in a move may be written without spaces around it (i.e. "A Par-Bur"). A coast
+// written apart from its province (i.e. "StP nc" or "StP (nc)") is kept with it, as "StP/nc".
+pub(crate) fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+
+    for token in input.replace('-', " - ").split_whitespace() {
+        match tokens.last_mut() {
+            Some(province) if province != "-" && is_coast_spec(token) => {
+                province.push('/');
+                province.push_str(token.trim_start_matches('(').trim_end_matches(')'));
+            }
+            _ => tokens.push(token.to_string()),
+        }
+    }
+
+    tokens
+}
+
+// Whether the token is one of the usual ways of writing a coast on its own: "nc", "NC", "n.c." or "(nc)", and the same for
+// the other three coasts.
+pub(crate) fn is_coast_spec(token: &str) -> bool {
+    let coast: String = token
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .chars()
+        .filter(|c| *c != '.')
+        .collect();
+    matches!(
+        coast.to_ascii_lowercase().as_str(),
+        "nc" | "sc" | "ec" | "wc"
+    )
+}
+
+pub(crate) fn parse_unit_type(token: &str) -> Result<UnitType, OrderError> {
+    match token.to_ascii_lowercase().as_str() {
+        "a" | "army" => Ok(UnitType::Army),
+        "f" | "fleet" => Ok(UnitType::Fleet),
+        _ => Err(OrderError::UnknownUnitType(token.to_string())),
+    }
+}
+
+pub(crate) fn parse_province_type(token: &str) -> Option<ProvinceType> {
+    match token.to_ascii_lowercase().as_str() {
+        "land" => Some(ProvinceType::Land),
+        "coast" => Some(ProvinceType::Coast),
+        "water" => Some(ProvinceType::Water),
+        "deepsea" => Some(ProvinceType::DeepSea),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_province(token: &str, map: &Map) -> Result<ProvinceID, OrderError> {
+    if let Some(province) = map.province_by_name(token) {
+        return Ok(province);
+    }
+
+    // Naming a coast that a province with coasts doesn't have is a mistake in the coast, not the province.
+    let has_coasts = split_coast(token)
+        .and_then(|(parent, _)| map.province_by_name(parent))
+        .and_then(|parent| map.province(parent))
+        .is_some_and(|parent| !parent.has_coasts().is_empty());
+    if has_coasts {
+        return Err(OrderError::UnknownCoast(token.to_string()));
+    }
+    Err(OrderError::UnknownProvince(token.to_string()))
+}
+
+// Splits a province name written with a coast into the province and the coast, i.e. "StP/nc", "StP(nc)" or "StP nc" into
+// "StP" and "nc". None if there is no coast in the name.
+pub(crate) fn split_coast(name: &str) -> Option<(&str, &str)> {
+    if let Some((parent, coast)) = name.split_once('/') {
+        return Some((parent.trim(), coast.trim()));
+    }
+    if let Some((parent, coast)) = name.split_once('(') {
+        return Some((parent.trim(), coast.strip_suffix(')')?.trim()));
+    }
+    name.trim()
+        .rsplit_once(' ')
+        .map(|(parent, coast)| (parent.trim_end(), coast))
+}
+
+// A CSV or TSV table, split into its columns and rows. Fields are split on tabs if the header has any, and on commas
+// otherwise, and every field is trimmed. Blank lines are skipped.
+pub(crate) struct Table<'a> {
+    columns: Vec<&'a str>,
+
+    // Each row along with its line number, counting from 1 for the header.
+    pub(crate) rows: Vec<(usize, Vec<&'a str>)>,
+}
+
+impl<'a> Table<'a> {
+    pub(crate) fn parse(input: &'a str) -> Self {
+        let mut lines = input
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let header = lines.next().map_or("", |(_, line)| line);
+        let separator = if header.contains('\t') { '\t' } else { ',' };
+        let split = |line: &'a str| line.split(separator).map(str::trim).collect();
+
+        Self {
+            columns: split(header),
+            rows: lines
+                .map(|(index, line)| (index + 1, split(line)))
+                .collect(),
+        }
+    }
+
+    // The position of the column called `name` in each row, ignoring case.
+    pub(crate) fn column(&self, name: &'static str) -> Result<usize, MapError> {
+        self.columns
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+            .ok_or(MapError::MissingColumn(name))
+    }
+}
@@ -1,6 +1,10 @@
 pub type PlayerID = u8;
 
+// The owner of anything not owned by a player, such as unclaimed supply centers. Never the ID of an actual player.
+pub const NEUTRAL: PlayerID = 0;
+
 /// This struct represents a player of the game Note that this is the 'internal' player, such as the "France", "Austria", etc. (Not the person playing the country)
+#[derive(Clone)]
 pub struct Player {
     // Internal ID number of the player
     player_id: PlayerID,
@@ -8,3 +12,31 @@ pub struct Player {
     // Human readable player name (i.e. "France")
     player_name: String,
 }
+
+impl Player {
+    pub fn new(player_id: PlayerID, player_name: String) -> Self {
+        Self {
+            player_id,
+            player_name,
+        }
+    }
+
+    // The seven powers of the standard map, numbered from 1 in alphabetical order.
+    pub fn standard_powers() -> Vec<Self> {
+        [
+            "Austria", "England", "France", "Germany", "Italy", "Russia", "Turkey",
+        ]
+        .into_iter()
+        .zip(1..)
+        .map(|(name, player_id)| Self::new(player_id, name.to_string()))
+        .collect()
+    }
+
+    pub fn player_id(&self) -> PlayerID {
+        self.player_id
+    }
+
+    pub fn player_name(&self) -> &str {
+        &self.player_name
+    }
+}
@@ -1,8 +1,13 @@
-use crate::unit::Unit;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    player::{NEUTRAL, PlayerID},
+    unit::{Unit, UnitType},
+};
 
-pub type PlayerID = u8;
 pub type ProvinceID = u8;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum ProvinceType {
     // These are the only 3 province types in standard Diplomacy.
     // Note that "Coast" specifically means locations like Spain North Coast, and Spain south Coast (and not Brest).
@@ -15,6 +20,22 @@ pub enum ProvinceType {
 }
 
 impl ProvinceType {
+    // Whether or not a unit of the given type can ever be in this space. Since `Land` includes coastal provinces such as
+    // Brest, fleets can be in land provinces too; whether a particular land province is inland depends on its connections.
+    // `Coast` is only ever one coast of a province with split coasts (e.g. Spain's north coast), so it holds fleets only,
+    // and an army there is in the parent province instead.
+    pub fn can_hold(&self, unit_type: &UnitType) -> bool {
+        match (self, unit_type) {
+            (Self::Land, _) => true,
+            (Self::Coast, UnitType::Army) => false,
+            (Self::Coast, UnitType::Fleet) => true,
+            (Self::Water, UnitType::Army) => false,
+            (Self::Water, UnitType::Fleet) => true,
+            (Self::DeepSea, UnitType::Army) => false,
+            (Self::DeepSea, UnitType::Fleet) => true,
+        }
+    }
+
     // Whether or not a unit can convoy through this space (i.e. can a unit legally order "Convoy X -> Y" here).
     pub fn can_convoy_through(&self) -> bool {
         match self {
@@ -47,6 +68,7 @@ impl ProvinceType {
 }
 
 /// Struct representing an individual province on the game board
+#[derive(Clone)]
 pub struct Province {
     // Internal ID of the province
     province_id: ProvinceID,
@@ -57,7 +79,7 @@ pub struct Province {
     // Type of the province (i.e. land/water/coast)
     province_type: ProvinceType,
 
-    // Owner of the province. Note that neutral/unowned is a value of `0`.
+    // Owner of the province. Neutral/unowned provinces are owned by `NEUTRAL`.
     owned_by: PlayerID,
 
     // None if not a Supply Center, Some(1) if an SC, and Some(0) if it is the coast of an SC.
@@ -80,10 +102,182 @@ pub struct Province {
     // Some(Unit) if there is a unit that was dislodged from this province, before it retreats. Should only be Some() during a retreat phase.
     disloged_unit: Option<Unit>,
 
+    // The province that the unit which dislodged `disloged_unit` moved from. `disloged_unit` may not retreat there.
+    dislodged_by: Option<ProvinceID>,
+
     // Whether or not this province can be retreated to. Only matters during retreat phase.
     available_for_retreat: bool,
 }
 
 impl Province {
-    pub fn new() {}
+    pub fn new(
+        province_id: ProvinceID,
+        province_name: String,
+        province_type: ProvinceType,
+    ) -> Self {
+        Self {
+            province_id,
+            province_name,
+            province_type,
+            owned_by: NEUTRAL,
+            sc_value: None,
+            core_of: Vec::new(),
+            has_coasts: Vec::new(),
+            is_coast_of: None,
+            occupied_by: None,
+            disloged_unit: None,
+            dislodged_by: None,
+            available_for_retreat: true,
+        }
+    }
+
+    pub fn province_id(&self) -> ProvinceID {
+        self.province_id
+    }
+
+    pub fn province_name(&self) -> &str {
+        &self.province_name
+    }
+
+    pub fn province_type(&self) -> ProvinceType {
+        self.province_type
+    }
+
+    pub fn owned_by(&self) -> PlayerID {
+        self.owned_by
+    }
+
+    pub fn is_neutral(&self) -> bool {
+        self.owned_by == NEUTRAL
+    }
+
+    pub fn sc_value(&self) -> Option<u8> {
+        self.sc_value
+    }
+
+    // Whether this province counts as a supply center. Coasts of supply centers (with an `sc_value` of 0) don't.
+    pub fn is_supply_center(&self) -> bool {
+        self.sc_value.is_some_and(|value| value > 0)
+    }
+
+    pub fn core_of(&self) -> &[PlayerID] {
+        &self.core_of
+    }
+
+    pub fn has_coasts(&self) -> &[ProvinceID] {
+        &self.has_coasts
+    }
+
+    pub fn is_coast_of(&self) -> Option<ProvinceID> {
+        self.is_coast_of
+    }
+
+    pub fn occupied_by(&self) -> Option<&Unit> {
+        self.occupied_by.as_ref()
+    }
+
+    pub fn disloged_unit(&self) -> Option<&Unit> {
+        self.disloged_unit.as_ref()
+    }
+
+    pub fn dislodged_by(&self) -> Option<ProvinceID> {
+        self.dislodged_by
+    }
+
+    pub fn available_for_retreat(&self) -> bool {
+        self.available_for_retreat
+    }
+
+    // Marks this province as a coast of `parent`. The parent's `has_coasts` is kept in sync when the province is added to a `Map`.
+    pub fn set_coast_of(&mut self, parent: ProvinceID) {
+        self.is_coast_of = Some(parent);
+    }
+
+    pub fn set_owner(&mut self, owner: PlayerID) {
+        self.owned_by = owner;
+    }
+
+    pub fn set_sc_value(&mut self, sc_value: Option<u8>) {
+        self.sc_value = sc_value;
+    }
+
+    pub fn add_core(&mut self, player: PlayerID) {
+        if !self.core_of.contains(&player) {
+            self.core_of.push(player);
+        }
+    }
+
+    // A province holds a single unit of either type, so this replaces any unit already here. For a province with split
+    // coasts, the province and its coasts share that one slot; movement treats them as one (see `Map::is_same_province`).
+    pub fn set_occupant(&mut self, unit: Option<Unit>) {
+        self.occupied_by = unit;
+    }
+
+    pub fn take_occupant(&mut self) -> Option<Unit> {
+        self.occupied_by.take()
+    }
+
+    pub fn set_disloged_unit(&mut self, unit: Option<Unit>) {
+        self.disloged_unit = unit;
+    }
+
+    pub fn take_disloged_unit(&mut self) -> Option<Unit> {
+        self.disloged_unit.take()
+    }
+
+    pub fn set_dislodged_by(&mut self, attacker_origin: Option<ProvinceID>) {
+        self.dislodged_by = attacker_origin;
+    }
+
+    pub fn set_available_for_retreat(&mut self, available: bool) {
+        self.available_for_retreat = available;
+    }
+
+    // Clears everything about the province that only lasts for a single turn, so that it doesn't carry over into the next:
+    // any standoff, and any unit dislodged from it along with where it was attacked from. A dislodged unit that is still
+    // here by then didn't retreat, so it is gone.
+    pub fn reset_turn_state(&mut self) {
+        self.available_for_retreat = true;
+        self.disloged_unit = None;
+        self.dislodged_by = None;
+    }
+
+    pub(crate) fn add_coast(&mut self, coast: ProvinceID) {
+        if !self.has_coasts.contains(&coast) {
+            self.has_coasts.push(coast);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn armies_stay_on_land_and_fleets_at_sea_or_on_coasts() {
+        use ProvinceType::*;
+
+        assert!(Land.can_hold(&UnitType::Army));
+        assert!(!Water.can_hold(&UnitType::Army));
+        assert!(!DeepSea.can_hold(&UnitType::Army));
+        assert!(!Coast.can_hold(&UnitType::Army));
+        // Whether a fleet can be in a land province depends on whether it has a coast to be on.
+        assert!(Land.can_hold(&UnitType::Fleet));
+        assert!(Water.can_hold(&UnitType::Fleet));
+        assert!(Coast.can_hold(&UnitType::Fleet));
+        assert!(DeepSea.can_hold(&UnitType::Fleet));
+    }
+
+    #[test]
+    fn reset_turn_state_clears_standoffs_and_dislodgements() {
+        let mut province = Province::new(3, "Bur".to_string(), ProvinceType::Land);
+        province.set_available_for_retreat(false);
+        province.set_disloged_unit(Some(Unit::new(4, UnitType::Army)));
+        province.set_dislodged_by(Some(2));
+
+        province.reset_turn_state();
+        assert!(province.available_for_retreat());
+        assert_eq!(province.disloged_unit(), None);
+        assert_eq!(province.dislodged_by(), None);
+    }
 }
@@ -0,0 +1,96 @@
+use crate::{
+    error::OrderError,
+    map::Map,
+    parse::{parse_province, parse_unit_type, tokenize},
+    province::ProvinceID,
+};
+
+/// An order given to a dislodged unit during a retreat phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetreatOrder {
+    Retreat { from: ProvinceID, to: ProvinceID },
+    Disband { from: ProvinceID },
+}
+
+impl RetreatOrder {
+    // Parses a retreat such as "A Par - Bur", or a disband such as "A Par D".
+    pub fn parse(input: &str, map: &Map) -> Result<Self, OrderError> {
+        let tokens = tokenize(input);
+
+        match tokens.as_slice() {
+            [unit_type, from, dash, to] if dash == "-" => {
+                parse_unit_type(unit_type)?;
+                Ok(Self::Retreat {
+                    from: parse_province(from, map)?,
+                    to: parse_province(to, map)?,
+                })
+            }
+            [unit_type, from, disband]
+                if disband.eq_ignore_ascii_case("d") || disband.eq_ignore_ascii_case("disband") =>
+            {
+                parse_unit_type(unit_type)?;
+                Ok(Self::Disband {
+                    from: parse_province(from, map)?,
+                })
+            }
+            _ => Err(OrderError::InvalidFormat(input.to_string())),
+        }
+    }
+
+    // The province the dislodged unit is retreating from.
+    pub fn from(&self) -> ProvinceID {
+        match self {
+            Self::Retreat { from, .. } => *from,
+            Self::Disband { from } => *from,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_retreat() {
+        let map = Map::standard();
+        let id = |name| map.province_by_name(name).unwrap();
+
+        assert_eq!(
+            RetreatOrder::parse("A Par - Bur", &map),
+            Ok(RetreatOrder::Retreat {
+                from: id("Par"),
+                to: id("Bur")
+            })
+        );
+        assert_eq!(
+            RetreatOrder::parse("F Spa/sc - MAO", &map),
+            Ok(RetreatOrder::Retreat {
+                from: id("Spa/sc"),
+                to: id("MAO")
+            })
+        );
+    }
+
+    #[test]
+    fn parses_a_disband() {
+        let map = Map::standard();
+        let paris = map.province_by_name("Par").unwrap();
+
+        assert_eq!(
+            RetreatOrder::parse("A Par D", &map),
+            Ok(RetreatOrder::Disband { from: paris })
+        );
+        assert_eq!(
+            RetreatOrder::parse("a par disband", &map),
+            Ok(RetreatOrder::Disband { from: paris })
+        );
+        assert_eq!(
+            RetreatOrder::parse("A Par H", &map),
+            Err(OrderError::InvalidFormat("A Par H".to_string()))
+        );
+        assert_eq!(
+            RetreatOrder::parse("A Xyz D", &map),
+            Err(OrderError::UnknownProvince("Xyz".to_string()))
+        );
+    }
+}
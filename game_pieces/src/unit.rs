@@ -1,12 +1,15 @@
-use crate::player::PlayerID;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq)]
+use crate::player::{Player, PlayerID};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum UnitType {
     // Base Game types
     Army,
     Fleet,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Unit {
     owner: PlayerID,
 
@@ -26,17 +29,73 @@ impl Unit {
         self.unit_type
     }
 
-    fn can_convoy(&self) -> bool {
+    // A human readable description of the unit, i.e. "French Army". Uses the owner's name as is if it isn't one of the
+    // standard powers, and "Unknown" if the owner isn't in `players`.
+    pub fn describe(&self, players: &[Player]) -> String {
+        let owner = players
+            .iter()
+            .find(|player| player.player_id() == self.owner)
+            .map(|player| match player.player_name() {
+                "Austria" => "Austrian",
+                "England" => "English",
+                "France" => "French",
+                "Germany" => "German",
+                "Italy" => "Italian",
+                "Russia" => "Russian",
+                "Turkey" => "Turkish",
+                name => name,
+            })
+            .unwrap_or("Unknown");
+
+        format!("{owner} {:?}", self.unit_type)
+    }
+
+    pub fn can_convoy(&self) -> bool {
         match self.unit_type {
             UnitType::Army => false,
             UnitType::Fleet => true,
         }
     }
 
-    fn can_be_convoyed(&self) -> bool {
+    pub fn can_be_convoyed(&self) -> bool {
         match self.unit_type {
             UnitType::Army => true,
             UnitType::Fleet => false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_are_equal_only_with_the_same_owner_and_type() {
+        assert_eq!(Unit::new(3, UnitType::Army), Unit::new(3, UnitType::Army));
+        assert_ne!(Unit::new(3, UnitType::Army), Unit::new(4, UnitType::Army));
+        assert_ne!(Unit::new(3, UnitType::Army), Unit::new(3, UnitType::Fleet));
+    }
+
+    #[test]
+    fn describe_names_the_owner() {
+        let mut players = Player::standard_powers();
+        players.push(Player::new(8, "Spain".to_string()));
+
+        assert_eq!(
+            Unit::new(3, UnitType::Army).describe(&players),
+            "French Army"
+        );
+        assert_eq!(
+            Unit::new(7, UnitType::Fleet).describe(&players),
+            "Turkish Fleet"
+        );
+        assert_eq!(
+            Unit::new(8, UnitType::Fleet).describe(&players),
+            "Spain Fleet"
+        );
+        assert_eq!(
+            Unit::new(9, UnitType::Army).describe(&players),
+            "Unknown Army"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::province::ProvinceType;
+
+// How a convoy paradox (a convoy whose success depends on itself) is settled. Different leagues use different rules.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum ParadoxRule {
+    // The convoyed armies involved in the paradox don't move at all.
+    #[default]
+    Szykman,
+
+    // Every unit involved in the paradox holds, so every move in it fails.
+    AllHold,
+
+    // The convoys involved in the paradox are disrupted. A convoyed army with a land route may still move over land.
+    DptgBackup,
+}
+
+/// Settings that differ between variants of the game, such as how many supply centers are needed to win.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VariantConfig {
+    // Number of supply centers a single player needs to own to win the game outright.
+    solo_win_threshold: u8,
+
+    // The year of the first turn of the game.
+    starting_year: u16,
+
+    // Province types that may appear on the map. `DeepSea` is only used by some variants.
+    enabled_province_types: Vec<ProvinceType>,
+
+    // The rule used to settle convoy paradoxes. Configs saved before this existed use the default.
+    #[serde(default)]
+    paradox_rule: ParadoxRule,
+
+    // Whether orders that could never be carried out (i.e. a move to a province the unit isn't next to) are treated as holds,
+    // rather than as illegal orders. Unlike an illegal order, a hold can be supported. Configs saved before this existed
+    // keep illegal orders.
+    #[serde(default)]
+    illegal_orders_become_holds: bool,
+}
+
+impl VariantConfig {
+    pub fn new(
+        solo_win_threshold: u8,
+        starting_year: u16,
+        enabled_province_types: Vec<ProvinceType>,
+    ) -> Self {
+        Self {
+            solo_win_threshold,
+            starting_year,
+            enabled_province_types,
+            paradox_rule: ParadoxRule::default(),
+            illegal_orders_become_holds: false,
+        }
+    }
+
+    // The rules of standard Diplomacy: 18 of the 34 supply centers to win, starting in 1901.
+    pub fn standard() -> Self {
+        Self::new(
+            18,
+            1901,
+            vec![ProvinceType::Land, ProvinceType::Coast, ProvinceType::Water],
+        )
+    }
+
+    pub fn solo_win_threshold(&self) -> u8 {
+        self.solo_win_threshold
+    }
+
+    pub fn starting_year(&self) -> u16 {
+        self.starting_year
+    }
+
+    pub fn enabled_province_types(&self) -> &[ProvinceType] {
+        &self.enabled_province_types
+    }
+
+    pub fn paradox_rule(&self) -> ParadoxRule {
+        self.paradox_rule
+    }
+
+    pub fn set_paradox_rule(&mut self, paradox_rule: ParadoxRule) {
+        self.paradox_rule = paradox_rule;
+    }
+
+    pub fn illegal_orders_become_holds(&self) -> bool {
+        self.illegal_orders_become_holds
+    }
+
+    pub fn set_illegal_orders_become_holds(&mut self, illegal_orders_become_holds: bool) {
+        self.illegal_orders_become_holds = illegal_orders_become_holds;
+    }
+
+    pub fn is_enabled(&self, province_type: &ProvinceType) -> bool {
+        self.enabled_province_types.contains(province_type)
+    }
+}
+
+impl Default for VariantConfig {
+    fn default() -> Self {
+        Self::standard()
+    }
+}